@@ -0,0 +1,56 @@
+//! Writes one test per part of each file in `examples/dayNN/`, so that adding an example doesn't need any code
+
+use std::{env, fmt::Write, fs, path::Path};
+
+fn main() {
+    println!("cargo:rerun-if-changed=examples");
+    let mut paths = Vec::new();
+    for day in fs::read_dir("examples").expect("Could not read examples/") {
+        let day = day.unwrap().path();
+        println!("cargo:rerun-if-changed={}", day.display());
+        for file in fs::read_dir(&day).unwrap() {
+            let path = file.unwrap().path();
+            if path.extension().is_some_and(|e| e == "txt") {
+                paths.push(path);
+            }
+        }
+    }
+    paths.sort();
+
+    let mut tests = String::new();
+    for path in paths {
+        let day = path.parent().and_then(Path::file_name).unwrap().to_string_lossy();
+        let name = path.file_stem().unwrap().to_string_lossy();
+        let test: String = format!("{day}_{name}")
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+            .collect();
+        let (parts, unsolved) = header_parts(&fs::read_to_string(&path).unwrap());
+        for part in parts {
+            writeln!(tests, "#[test]").unwrap();
+            if unsolved.contains(&part) {
+                writeln!(tests, "#[ignore = \"part {part} is not solved yet\"]").unwrap();
+            }
+            let path = path.display().to_string();
+            writeln!(tests, "fn {test}_part{part}() {{\n    check({path:?}, {part});\n}}\n").unwrap();
+        }
+    }
+    fs::write(Path::new(&env::var("OUT_DIR").unwrap()).join("examples.rs"), tests).unwrap();
+}
+
+/// The parts that an example has answers for, and the parts it lists as unsolved. The rest of the header is checked
+/// by `Example::parse` when the tests run.
+fn header_parts(text: &str) -> (Vec<u8>, Vec<u8>) {
+    let (mut parts, mut unsolved) = (Vec::new(), Vec::new());
+    for line in text.lines().take_while(|line| line.trim() != "---") {
+        match line.split_once('=').map(|(key, value)| (key.trim(), value)) {
+            Some(("part1", _)) => parts.push(1),
+            Some(("part2", _)) => parts.push(2),
+            Some(("unsolved", value)) => {
+                unsolved.extend(value.split(',').filter_map(|part| part.trim().parse::<u8>().ok()))
+            }
+            _ => {}
+        }
+    }
+    (parts, unsolved)
+}
@@ -0,0 +1,10 @@
+#![no_main]
+
+use aoc_2023::solution::{Day01, Solution};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    // There is no separate parsing step, but both parts only take a single pass over the input
+    let _ = Day01.part1(input);
+    let _ = Day01.part2(input);
+});
@@ -0,0 +1,8 @@
+#![no_main]
+
+use aoc_2023::solution::{Day07, Solution};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = Day07.parse(input);
+});
@@ -0,0 +1,201 @@
+use anyhow::{bail, Context, Error, Result};
+use std::{collections::HashMap, str::FromStr};
+
+use crate::solution::{Answer, Unimplemented};
+
+/// The known answers for each (day, part), read from a small subset of TOML:
+///
+/// ```toml
+/// [day01]
+/// part1 = 55130
+/// part2 = "54985"
+/// ```
+#[derive(Default, Debug, PartialEq)]
+pub struct Answers(HashMap<(u8, u8), String>);
+
+/// One `partN = value` or `partN_field = value` line of the TOML subset shared by the answers and state files
+#[derive(Debug, PartialEq)]
+pub(crate) struct Entry<'a> {
+    /// The line number, counting from 1
+    pub line: usize,
+    pub day: u8,
+    pub part: u8,
+    /// The part of the key after `partN_`, or "" for a bare `partN`
+    pub field: &'a str,
+    /// The value without any quotes around it
+    pub value: &'a str,
+}
+
+/// Reads every entry under the `[dayNN]` headers, skipping blank lines and `#` comments
+pub(crate) fn entries(input: &str) -> Result<Vec<Entry<'_>>> {
+    let mut entries = Vec::new();
+    let mut day: Option<u8> = None;
+    for (i, line) in input.lines().enumerate() {
+        let line = line.split_once('#').map_or(line, |(l, _)| l).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[') {
+            let d = header
+                .strip_suffix(']')
+                .and_then(|h| h.strip_prefix("day"))
+                .with_context(|| format!("line {}: expected a [dayNN] header, got {line}", i + 1))?;
+            day = Some(d.parse().with_context(|| format!("line {}: invalid day {d}", i + 1))?);
+        } else {
+            let (key, value) = line.split_once('=').with_context(|| format!("line {}: expected key = value", i + 1))?;
+            let (part, field) = key.trim().split_once('_').unwrap_or((key.trim(), ""));
+            let part: u8 = match part {
+                "part1" => 1,
+                "part2" => 2,
+                k => bail!("line {}: expected part1 or part2, got {k}", i + 1),
+            };
+            let day = day.with_context(|| format!("line {}: value given before any [dayNN] header", i + 1))?;
+            let value = value.trim();
+            let value = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value);
+            entries.push(Entry { line: i + 1, day, part, field, value });
+        }
+    }
+    Ok(entries)
+}
+
+impl FromStr for Answers {
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Self> {
+        let mut answers = Self::default();
+        for entry in entries(input)? {
+            if !entry.field.is_empty() {
+                bail!("line {}: unknown field {}", entry.line, entry.field);
+            }
+            answers.0.insert((entry.day, entry.part), entry.value.to_string());
+        }
+        Ok(answers)
+    }
+}
+
+impl Answers {
+    pub fn load(path: &str) -> Result<Self> {
+        std::fs::read_to_string(path).with_context(|| format!("Could not read {path}"))?.parse()
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+}
+
+/// The outcome of checking one part against its known answer
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass {
+        actual: String,
+    },
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// The part was solved, but there is no known answer to compare with
+    Missing {
+        actual: String,
+    },
+    Unsolved,
+    Error(String),
+}
+
+impl Verdict {
+    pub fn new(expected: Option<&str>, result: Result<Answer>) -> Self {
+        match (expected, result) {
+            (_, Err(e)) if e.is::<Unimplemented>() => Self::Unsolved,
+            (_, Err(e)) => Self::Error(format!("{e:#}")),
+            (None, Ok(answer)) => Self::Missing { actual: answer.to_string() },
+            (Some(expected), Ok(answer)) if expected == answer.to_string() => Self::Pass { actual: answer.to_string() },
+            (Some(expected), Ok(answer)) => Self::Fail { expected: expected.to_string(), actual: answer.to_string() },
+        }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Fail { .. } | Self::Error(_))
+    }
+
+    /// The answer that was computed, if there was one
+    pub fn answer(&self) -> Option<&str> {
+        match self {
+            Self::Pass { actual } | Self::Fail { actual, .. } | Self::Missing { actual } => Some(actual),
+            Self::Unsolved | Self::Error(_) => None,
+        }
+    }
+
+    /// Whether the computed answer matched the known answer, if both exist
+    pub fn matched(&self) -> Option<bool> {
+        match self {
+            Self::Pass { .. } => Some(true),
+            Self::Fail { .. } => Some(false),
+            Self::Missing { .. } | Self::Unsolved | Self::Error(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pass { .. } => write!(f, "pass"),
+            Self::Fail { expected, actual } => write!(f, "FAIL (expected {expected}, got {actual})"),
+            Self::Missing { actual } => write!(f, "missing (got {actual})"),
+            Self::Unsolved => write!(f, "unsolved"),
+            Self::Error(e) => write!(f, "ERROR ({e})"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    const EXAMPLE: &str = "# A comment
+[day01]
+part1 = 142
+part2 = \"281\" # Quotes are optional
+
+[day25]
+part1 = 54
+";
+
+    #[test]
+    fn test_parse_answers() {
+        let answers: Answers = EXAMPLE.parse().unwrap();
+        assert_eq!(answers.get(1, 1), Some("142"));
+        assert_eq!(answers.get(1, 2), Some("281"));
+        assert_eq!(answers.get(25, 1), Some("54"));
+        assert_eq!(answers.get(25, 2), None);
+        assert!("part1 = 1".parse::<Answers>().is_err());
+        assert!("[day01]\npart3 = 1".parse::<Answers>().is_err());
+        assert!("[day01]\npart1_elapsed_ms = 1".parse::<Answers>().is_err());
+    }
+
+    #[test]
+    fn test_entries() {
+        let read = entries("[day07] # Camel cards\npart2_verified = \"true\"\n").unwrap();
+        assert_eq!(read, [Entry { line: 2, day: 7, part: 2, field: "verified", value: "true" }]);
+        assert_eq!(entries("[day1").unwrap_err().to_string(), "line 1: expected a [dayNN] header, got [day1");
+    }
+
+    #[test]
+    fn test_verdict() {
+        assert_eq!(Verdict::new(Some("142"), Ok(Answer::Unsigned(142))), Verdict::Pass { actual: "142".to_string() });
+        assert_eq!(
+            Verdict::new(Some("142"), Ok(Answer::Signed(-1))),
+            Verdict::Fail { expected: "142".to_string(), actual: "-1".to_string() }
+        );
+        assert_eq!(Verdict::new(None, Ok(Answer::Unsigned(1))), Verdict::Missing { actual: "1".to_string() });
+        assert_eq!(Verdict::new(Some("1"), Err(anyhow!(Unimplemented))), Verdict::Unsolved);
+        assert!(Verdict::new(None, Err(anyhow!("oops"))).is_failure());
+        assert_eq!(Verdict::new(Some("2"), Ok(Answer::Unsigned(1))).answer(), Some("1"));
+        assert_eq!(Verdict::new(Some("2"), Ok(Answer::Unsigned(1))).matched(), Some(false));
+        assert_eq!(Verdict::new(None, Ok(Answer::Unsigned(1))).matched(), None);
+    }
+
+    #[test]
+    fn test_answers_file() {
+        let answers = Answers::load("answers.toml").unwrap();
+        assert_eq!(answers.get(1, 1), Some("55130"));
+    }
+}
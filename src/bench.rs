@@ -0,0 +1,107 @@
+use anyhow::Result;
+use std::time::{Duration, Instant};
+
+use crate::{
+    parse,
+    solution::{self, Solution, Unimplemented},
+};
+
+/// The spread of a set of timings
+#[derive(Debug)]
+pub struct Summary {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Summary {
+    fn new(mut timings: Vec<Duration>) -> Option<Self> {
+        timings.sort();
+        Some(Self { min: *timings.first()?, median: timings[timings.len() / 2], max: *timings.last()? })
+    }
+}
+
+#[derive(Debug)]
+pub struct Timings {
+    /// Not every day has a separate parsing step
+    pub parse: Option<Summary>,
+    /// The time for the whole part, parsing included, as the parts parse their input themselves
+    pub total: Summary,
+}
+
+/// Times one part of a solution over a number of runs, and how much of that is parsing where a day parses separately
+pub fn time_part(solution: &dyn Solution, part: u8, input: &str, runs: usize) -> Result<Timings> {
+    let mut parse_timings: Vec<Duration> = Vec::new();
+    let mut total_timings: Vec<Duration> = Vec::new();
+    let mut has_parse = true;
+
+    for _ in 0..runs.max(1) {
+        if has_parse {
+            let start = Instant::now();
+            match solution.parse(input) {
+                Ok(()) => parse_timings.push(start.elapsed()),
+                Err(e) if e.is::<Unimplemented>() => has_parse = false,
+                Err(e) => return Err(parse::diagnose(e, input)),
+            }
+        }
+
+        let start = Instant::now();
+        solution::solve(solution, part, input)?;
+        total_timings.push(start.elapsed());
+    }
+
+    Ok(Timings {
+        parse: Summary::new(parse_timings),
+        total: Summary::new(total_timings).expect("there is always at least one run"),
+    })
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64() * 1000.0)
+}
+
+pub fn print_header() {
+    println!(
+        "{:<6} {:>12} {:>12} {:>12} {:>12} {:>12} {:>12}",
+        "", "parse min", "median", "max", "total min", "median", "max"
+    );
+}
+
+pub fn print_row(day: u8, part: u8, timings: &Timings) {
+    let [parse_min, parse_median, parse_max] = match &timings.parse {
+        Some(parse) => [parse.min, parse.median, parse.max].map(millis),
+        None => ["-".to_string(), "-".to_string(), "-".to_string()],
+    };
+    println!(
+        "{:<6} {:>12} {:>12} {:>12} {:>12} {:>12} {:>12}",
+        format!("{day:02}-{part}"),
+        parse_min,
+        parse_median,
+        parse_max,
+        millis(timings.total.min),
+        millis(timings.total.median),
+        millis(timings.total.max),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary() {
+        let summary = Summary::new([3, 1, 2].map(Duration::from_millis).to_vec()).unwrap();
+        assert_eq!(summary.min, Duration::from_millis(1));
+        assert_eq!(summary.median, Duration::from_millis(2));
+        assert_eq!(summary.max, Duration::from_millis(3));
+        assert!(Summary::new(vec![]).is_none());
+    }
+
+    #[test]
+    fn test_time_part() {
+        let timings = time_part(&solution::Day06, 1, "Time:      7  15   30\nDistance:  9  40  200\n", 3).unwrap();
+        assert!(timings.parse.is_some());
+        assert!(time_part(&solution::Day01, 1, "1abc2\n", 3).unwrap().parse.is_none());
+        assert!(time_part(&solution::Day25, 2, "", 3).unwrap_err().is::<Unimplemented>());
+    }
+}
@@ -0,0 +1,152 @@
+use num::Integer;
+use std::{collections::HashMap, hash::Hash};
+
+/// The shape of an eventually periodic sequence `start, step(start), step(step(start)), ...`
+/// Every detector here loops forever if the sequence never repeats.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cycle {
+    /// The number of steps taken before the sequence first enters the cycle
+    pub tail: usize,
+    /// The number of steps it takes to go once around the cycle
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The earliest step (less than `tail + period`) whose state is the same as the state after `n` steps
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.tail {
+            n
+        } else {
+            self.tail + (n - self.tail) % self.period
+        }
+    }
+
+    /// The state after `n` steps, found by taking at most `tail + period` steps from `start`
+    pub fn state_at<S>(&self, start: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+        (0..self.reduce(n)).fold(start, |state, _| step(&state))
+    }
+}
+
+/// Floyd's tortoise and hare: constant memory, but steps each state up to three times
+pub fn floyd<S: Clone + PartialEq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    // The hare is now a multiple of the period ahead, so they meet again at the start of the cycle
+    let mut tail = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        tail += 1;
+    }
+
+    let mut period = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+    Cycle { tail, period }
+}
+
+/// Brent's algorithm: constant memory, and fewer steps than Floyd's
+pub fn brent<S: Clone + PartialEq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // Start the hare one period ahead, so they meet at the start of the cycle
+    let mut tail = 0;
+    tortoise = start.clone();
+    hare = (0..period).fold(start, |state, _| step(&state));
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        tail += 1;
+    }
+    Cycle { tail, period }
+}
+
+/// Remembers every state, stepping each once. Also returns the first `tail + period` states,
+/// so that the state after `n` steps is `states[cycle.reduce(n)]`.
+pub fn hashed<S: Clone + Hash + Eq>(start: S, mut step: impl FnMut(&S) -> S) -> (Cycle, Vec<S>) {
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut states: Vec<S> = Vec::new();
+    let mut state = start;
+    loop {
+        if let Some(&first) = seen.get(&state) {
+            return (Cycle { tail: first, period: states.len() - first }, states);
+        }
+        seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+/// Combines x ≡ a (mod m) and x ≡ b (mod n) into a single x ≡ c (mod lcm(m, n)), if there is a solution
+pub fn chinese_remainder((a, m): (i128, i128), (b, n): (i128, i128)) -> Option<(i128, i128)> {
+    let egcd = m.extended_gcd(&n);
+    if (b - a) % egcd.gcd != 0 {
+        return None;
+    }
+    let lcm = m / egcd.gcd * n;
+    let k = ((b - a) / egcd.gcd * egcd.x).rem_euclid(n / egcd.gcd);
+    Some(((a + m * k).rem_euclid(lcm), lcm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(x: &u64) -> u64 {
+        (x * x + 1) % 1009
+    }
+
+    #[test]
+    fn test_detectors_agree() {
+        for start in [0, 3, 42, 1000] {
+            let (cycle, states) = hashed(start, step);
+            assert_eq!(floyd(start, step), cycle);
+            assert_eq!(brent(start, step), cycle);
+            assert_eq!(states.len(), cycle.tail + cycle.period);
+
+            let mut state = start;
+            for n in 0..3 * states.len() {
+                assert_eq!(states[cycle.reduce(n)], state);
+                assert_eq!(cycle.state_at(start, step, n), state);
+                state = step(&state);
+            }
+        }
+    }
+
+    #[test]
+    fn test_chinese_remainder() {
+        assert_eq!(chinese_remainder((2, 3), (3, 5)), Some((8, 15)));
+        assert_eq!(chinese_remainder((1, 4), (3, 6)), Some((9, 12)));
+        assert_eq!(chinese_remainder((0, 4), (1, 6)), None);
+    }
+
+    #[test]
+    fn test_pure_cycle() {
+        let cycle = brent(0, |x| (x + 1) % 7);
+        assert_eq!(cycle, Cycle { tail: 0, period: 7 });
+        assert_eq!(cycle.reduce(1_000_000_000), 1_000_000_000 % 7);
+    }
+}
@@ -1,4 +1,8 @@
-pub fn part1(input: &str) -> u32 {
+use anyhow::{Context, Result};
+
+use crate::parse::Locate;
+
+pub fn part1(input: &str) -> Result<u32> {
     let mut total: u32 = 0;
     for l in input.lines() {
         let mut first: Option<u32> = None;
@@ -12,9 +16,10 @@ pub fn part1(input: &str) -> u32 {
                 last = digit;
             }
         }
-        total += first.unwrap() * 10 + last.unwrap();
+        let (first, last) = first.zip(last).context("Expected a digit in the line").at(l)?;
+        total += first * 10 + last;
     }
-    total
+    Ok(total)
 }
 
 #[derive(Debug, PartialEq)]
@@ -141,28 +146,25 @@ impl WordNumber {
 fn to_digit_sequence(mut input: &str) -> Vec<u32> {
     let mut digit_sequence: Vec<u32> = Vec::new();
 
-    while !input.is_empty() {
-        // Slice is not empty, unwrap is safe
-        if let Some(digit) = input.chars().next().unwrap().to_digit(10) {
+    while let Some(c) = input.chars().next() {
+        if let Some(digit) = c.to_digit(10) {
             digit_sequence.push(digit);
         } else if let Some(word_number) = WordNumber::from_front(input) {
             digit_sequence.push(word_number.to_digit());
         }
-        input = &input[1..];
+        input = &input[c.len_utf8()..];
     }
 
     digit_sequence
 }
 
-fn calibration_value(input_line: &str) -> u32 {
+fn calibration_value(input_line: &str) -> Result<u32> {
     let digits = to_digit_sequence(input_line);
-    // Assume input has at least one digit (or we panic)
-    let start = digits.first().unwrap();
-    let end = digits.last().unwrap();
-    start * 10 + end
+    let (start, end) = digits.first().zip(digits.last()).context("Expected a digit in the line").at(input_line)?;
+    Ok(start * 10 + end)
 }
 
-pub fn part2(input: &str) -> u32 {
+pub fn part2(input: &str) -> Result<u32> {
     input.lines().map(calibration_value).sum()
 }
 
@@ -170,36 +172,6 @@ pub fn part2(input: &str) -> u32 {
 mod tests {
     use super::*;
 
-    const EXAMPLE: &str = "1abc2
-pqr3stu8vwx
-a1b2c3d4e5f
-treb7uchet";
-
-    const EXAMPLE_P2: &str = "two1nine
-eightwothree
-abcone2threexyz
-xtwone3four
-4nineeightseven2
-zoneight234
-7pqrstsixteen";
-
-    #[test]
-    fn test_example() {
-        assert_eq!(part1(EXAMPLE), 142);
-    }
-
-    #[test]
-    fn test_example_p2() {
-        assert_eq!(part2(EXAMPLE_P2), 281);
-    }
-
-    #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day01.txt").unwrap();
-        assert_eq!(part1(&input), 55130);
-        assert_eq!(part2(&input), 54985);
-    }
-
     #[test]
     fn test_word_number_from_front() {
         assert_eq!(WordNumber::from_front("one").unwrap(), WordNumber::One);
@@ -232,13 +204,13 @@ zoneight234
 
     #[test]
     fn test_calibration_value() {
-        assert_eq!(calibration_value("eightwothree"), 83);
-        assert_eq!(calibration_value("oneight"), 18);
-        assert_eq!(calibration_value("3oneight"), 38);
-        assert_eq!(calibration_value("oneight9"), 19);
-        assert_eq!(calibration_value("one"), 11);
-        assert_eq!(calibration_value("onety"), 11);
-        assert_eq!(calibration_value("2asdf"), 22);
-        assert_eq!(calibration_value("9"), 99);
+        assert_eq!(calibration_value("eightwothree").unwrap(), 83);
+        assert_eq!(calibration_value("oneight").unwrap(), 18);
+        assert_eq!(calibration_value("3oneight").unwrap(), 38);
+        assert_eq!(calibration_value("oneight9").unwrap(), 19);
+        assert_eq!(calibration_value("one").unwrap(), 11);
+        assert_eq!(calibration_value("onety").unwrap(), 11);
+        assert_eq!(calibration_value("2asdf").unwrap(), 22);
+        assert_eq!(calibration_value("9").unwrap(), 99);
     }
 }
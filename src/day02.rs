@@ -1,6 +1,8 @@
-use anyhow::{bail, Error, Result};
+use anyhow::{anyhow, Error, Result};
 use std::str::FromStr;
 
+use crate::parse::Locate;
+
 #[derive(Default, Debug, PartialEq)]
 struct Cubes {
     red: u32,
@@ -16,15 +18,15 @@ impl FromStr for Cubes {
 
         for pair in s.split(',') {
             if let Some((num, color)) = pair.trim().split_once(' ') {
-                let n = num.parse::<u32>()?;
+                let n = num.parse::<u32>().at(num)?;
                 match color {
                     "red" => set.red += n,
                     "green" => set.green += n,
                     "blue" => set.blue += n,
-                    _ => bail!("Expected red, green, or blue. Got {color}"),
+                    _ => return Err(anyhow!("Expected red, green, or blue. Got {color}")).at(color),
                 }
             } else {
-                bail!("Expected a space-separated tuple, but {pair} could not be parsed");
+                return Err(anyhow!("Expected a space-separated tuple, but {pair} could not be parsed")).at(pair);
             }
         }
 
@@ -47,7 +49,7 @@ impl Cubes {
 }
 
 #[derive(Default, Debug, PartialEq)]
-struct Game {
+pub struct Game {
     id: u32,
     hands: Vec<Cubes>,
 }
@@ -59,9 +61,9 @@ impl FromStr for Game {
         let mut game = Self::default();
         if let Some((header, games)) = s.trim().split_once(':') {
             if let Some(id) = header.strip_prefix("Game ") {
-                game.id = id.parse()?;
+                game.id = id.parse().at(id)?;
             } else {
-                bail!("Expected the header to start with 'Game ', but it did not: {header}");
+                return Err(anyhow!("Expected the header to start with 'Game ', but it did not: {header}")).at(header);
             }
             for hand in games.split(';') {
                 game.hands.push(hand.parse()?);
@@ -71,7 +73,7 @@ impl FromStr for Game {
     }
 }
 
-fn parse_input(input: &str) -> Result<Vec<Game>> {
+pub fn parse_input(input: &str) -> Result<Vec<Game>> {
     let mut games: Vec<Game> = Vec::new();
     for input_line in input.lines() {
         games.push(input_line.parse()?);
@@ -91,26 +93,6 @@ pub fn part2(input: &str) -> Result<u32> {
 mod tests {
     use super::*;
 
-    const EXAMPLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
-";
-
-    #[test]
-    fn test_example() {
-        assert_eq!(part1(EXAMPLE).unwrap(), 8);
-        assert_eq!(part2(EXAMPLE).unwrap(), 2286);
-    }
-
-    #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day02.txt").unwrap();
-        assert_eq!(part1(&input).unwrap(), 2551);
-        assert_eq!(part2(&input).unwrap(), 62811);
-    }
-
     #[test]
     fn test_cubes_from_str() {
         assert_eq!(" 3 blue, 4 red".parse::<Cubes>().unwrap(), Cubes { green: 0, red: 4, blue: 3 });
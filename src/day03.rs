@@ -1,4 +1,6 @@
-type Posn = (usize, usize);
+use anyhow::{Context, Result};
+
+use crate::grid::{Grid, Posn};
 
 struct Number {
     value: u32,
@@ -19,10 +21,10 @@ struct Symbol {
     posn: Posn,
 }
 
-fn get_numbers(matrix: &[Vec<char>]) -> Vec<Number> {
+fn get_numbers(matrix: &Grid<char>) -> Result<Vec<Number>> {
     let mut numbers: Vec<Number> = Vec::new();
 
-    for (i, row) in matrix.iter().enumerate() {
+    for (i, row) in matrix.rows().enumerate() {
         let mut current_start: Option<usize> = None;
         let mut current_digits: String = String::new();
         for (j, &val) in row.iter().enumerate() {
@@ -33,7 +35,7 @@ fn get_numbers(matrix: &[Vec<char>]) -> Vec<Number> {
                 current_digits.push(val);
             } else if let Some(start_column) = current_start {
                 numbers.push(Number {
-                    value: current_digits.parse().unwrap(),
+                    value: current_digits.parse().with_context(|| format!("Bad part number {current_digits}"))?,
                     start: (i, start_column),
                     length: current_digits.len(),
                 });
@@ -45,38 +47,34 @@ fn get_numbers(matrix: &[Vec<char>]) -> Vec<Number> {
         // Deal with digits at the end of the line
         if let Some(start_column) = current_start {
             numbers.push(Number {
-                value: current_digits.parse().unwrap(),
+                value: current_digits.parse().with_context(|| format!("Bad part number {current_digits}"))?,
                 start: (i, start_column),
                 length: current_digits.len(),
             });
         }
     }
 
-    numbers
+    Ok(numbers)
 }
 
-fn get_symbols(matrix: &[Vec<char>]) -> Vec<Symbol> {
-    let mut symbols: Vec<Symbol> = Vec::new();
-    for (i, line) in matrix.iter().enumerate() {
-        for (j, &c) in line.iter().enumerate() {
-            if !c.is_ascii_digit() && c != '.' {
-                symbols.push(Symbol { value: c, posn: (i, j) });
-            }
-        }
-    }
-    symbols
+fn get_symbols(matrix: &Grid<char>) -> Vec<Symbol> {
+    matrix
+        .iter()
+        .filter(|(_, c)| !c.is_ascii_digit() && **c != '.')
+        .map(|(posn, &value)| Symbol { value, posn })
+        .collect()
 }
 
-pub fn part1(input: &str) -> u32 {
-    let matrix: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
-    let numbers = get_numbers(&matrix);
+pub fn part1(input: &str) -> Result<u32> {
+    let matrix: Grid<char> = input.parse()?;
+    let numbers = get_numbers(&matrix)?;
     let symbols = get_symbols(&matrix);
-    numbers.into_iter().filter(|n| symbols.iter().any(|s| n.is_adjacent(&s.posn))).map(|n| n.value).sum()
+    Ok(numbers.into_iter().filter(|n| symbols.iter().any(|s| n.is_adjacent(&s.posn))).map(|n| n.value).sum())
 }
 
-pub fn part2(input: &str) -> u32 {
-    let matrix: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
-    let numbers = get_numbers(&matrix);
+pub fn part2(input: &str) -> Result<u32> {
+    let matrix: Grid<char> = input.parse()?;
+    let numbers = get_numbers(&matrix)?;
     let symbols = get_symbols(&matrix);
 
     let mut gear_ratio_sum = 0;
@@ -89,35 +87,5 @@ pub fn part2(input: &str) -> u32 {
             gear_ratio_sum += first.value * second.value;
         } // Any other amount of adjacent numbers implies this is not a gear
     }
-    gear_ratio_sum
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "467..114..
-...*......
-..35..633.
-......#...
-617*......
-.....+.58.
-..592.....
-......755.
-...$.*....
-.664.598..
-";
-
-    #[test]
-    fn test_example() {
-        assert_eq!(part1(EXAMPLE), 4361);
-        assert_eq!(part2(EXAMPLE), 467835);
-    }
-
-    #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day03.txt").unwrap();
-        assert_eq!(part1(&input), 507214);
-        assert_eq!(part2(&input), 72553319);
-    }
+    Ok(gear_ratio_sum)
 }
@@ -1,6 +1,8 @@
-use anyhow::{anyhow, Context, Error, Result};
+use anyhow::{anyhow, bail, Context, Error, Result};
 use std::{ops::Range, str::FromStr};
 
+use crate::{interval::IntervalSet, parse::Locate};
+
 #[derive(Debug, PartialEq)]
 struct Map {
     dest: usize,
@@ -12,9 +14,9 @@ impl FromStr for Map {
     type Err = Error;
     fn from_str(input: &str) -> Result<Self> {
         if let [d, s, l] = input.split_ascii_whitespace().collect::<Vec<&str>>()[..] {
-            Ok(Self { dest: d.parse()?, source: s.parse()?, length: l.parse()? })
+            Ok(Self { dest: d.parse().at(d)?, source: s.parse().at(s)?, length: l.parse().at(l)? })
         } else {
-            Err(anyhow!("Could not parse three parts from {input}"))
+            Err(anyhow!("Could not parse three numbers from the map line")).at(input)
         }
     }
 }
@@ -24,10 +26,6 @@ impl Map {
         self.source..self.source + self.length
     }
 
-    fn range(&self) -> Range<usize> {
-        self.dest..self.dest + self.length
-    }
-
     fn map(&self, value: usize) -> Option<usize> {
         if self.domain().contains(&value) {
             Some(self.dest + value - self.source)
@@ -35,29 +33,6 @@ impl Map {
             None
         }
     }
-
-    fn map_range(&self, values: Range<usize>) -> (Range<usize>, Range<usize>, Range<usize>) {
-        // Returns a triple (before, mapped_values, after)
-        if values.is_empty() {
-            return (Range::default(), Range::default(), Range::default());
-        }
-        match (self.map(values.start), self.map(values.end)) {
-            (Some(start), Some(end)) => (Range::default(), start..end, Range::default()),
-            (None, Some(end)) => (values.start..self.domain().start, self.range().start..end, Range::default()),
-            (Some(start), None) => (Range::default(), start..self.range().end, self.domain().end..values.end),
-            (None, None) => {
-                if values.start < self.domain().start {
-                    if values.end >= self.domain().end {
-                        (values.start..self.domain().start, self.range(), self.domain().end..values.end)
-                    } else {
-                        (values, Range::default(), Range::default())
-                    }
-                } else {
-                    (Range::default(), Range::default(), values)
-                }
-            }
-        }
-    }
 }
 
 #[derive(Default, Debug, PartialEq)]
@@ -79,32 +54,13 @@ impl Maps {
         self.0.iter().find_map(|m| m.map(value)).unwrap_or(value)
     }
 
-    fn map_ranges(&self, mut unmapped: Vec<Range<usize>>) -> Vec<Range<usize>> {
-        let mut mapped: Vec<Range<usize>> = Vec::new();
-
-        for map in self.0.iter() {
-            let mut new_unmapped: Vec<Range<usize>> = Vec::new();
-            for range in unmapped.into_iter() {
-                let (unmapped_before, mapped_values, unmapped_after) = map.map_range(range);
-                if !unmapped_before.is_empty() {
-                    new_unmapped.push(unmapped_before);
-                }
-                if !unmapped_after.is_empty() {
-                    new_unmapped.push(unmapped_after)
-                }
-                if !mapped_values.is_empty() {
-                    mapped.push(mapped_values);
-                }
-            }
-            unmapped = new_unmapped;
-        }
-        mapped.extend(unmapped); // Unmapped values are mapped identically
-        mapped
+    fn map_ranges(&self, values: &IntervalSet) -> IntervalSet {
+        values.remap(self.0.iter().map(|m| (m.domain(), m.dest)))
     }
 }
 
 #[derive(Debug, PartialEq)]
-struct Almanac {
+pub struct Almanac {
     seeds: Vec<usize>,
     maps: Vec<Maps>,
 }
@@ -116,8 +72,8 @@ impl FromStr for Almanac {
         let input_sections: Vec<&str> = input.split("\n\n").collect();
         if let [s, s2s, s2f, f2w, w2l, l2t, t2h, h2l] = input_sections[..] {
             let mut seeds: Vec<usize> = Vec::new();
-            for seed in s.strip_prefix("seeds: ").context("Missing seeds header")?.split_ascii_whitespace() {
-                seeds.push(seed.parse()?);
+            for seed in s.strip_prefix("seeds: ").context("Missing seeds header").at(s)?.split_ascii_whitespace() {
+                seeds.push(seed.parse().at(seed)?);
             }
             Ok(Self {
                 seeds,
@@ -132,13 +88,13 @@ impl FromStr for Almanac {
                 ],
             })
         } else {
-            Err(anyhow!("Could not parse all of the sections from the input"))
+            Err(anyhow!("Expected 8 sections separated by blank lines, got {}", input_sections.len())).at(input)
         }
     }
 }
 
 impl Almanac {
-    fn seed_location(&self, seed: usize) -> usize {
+    pub fn seed_location(&self, seed: usize) -> usize {
         let mut current = seed;
         for map in &self.maps {
             current = map.map(current);
@@ -146,25 +102,25 @@ impl Almanac {
         current
     }
 
-    fn seed_locations(&self) -> Vec<usize> {
+    pub fn seed_locations(&self) -> Vec<usize> {
         self.seeds.iter().map(|&seed| self.seed_location(seed)).collect()
     }
 
-    fn seed_ranges(&self) -> Vec<Range<usize>> {
+    pub fn seed_ranges(&self) -> Result<Vec<Range<usize>>> {
         // The interpretation of seeds as per part 2
         let mut seed_ranges: Vec<Range<usize>> = Vec::new();
         for chunk in self.seeds.chunks(2) {
             if let &[start, length] = chunk {
-                seed_ranges.push(start..start + length);
+                seed_ranges.push(start..start.checked_add(length).context("Seed range is too long")?);
             } else {
-                panic!("Did not expect an odd number of seeds")
+                bail!("Did not expect an odd number of seeds")
             }
         }
-        seed_ranges
+        Ok(seed_ranges)
     }
 
-    fn seed_range_locations(&self) -> Vec<Range<usize>> {
-        self.maps.iter().fold(self.seed_ranges(), |current, map| map.map_ranges(current))
+    pub fn seed_range_locations(&self) -> Result<IntervalSet> {
+        Ok(self.maps.iter().fold(self.seed_ranges()?.into_iter().collect(), |current, map| map.map_ranges(&current)))
     }
 }
 
@@ -175,47 +131,13 @@ pub fn part1(input: &str) -> Result<usize> {
 
 pub fn part2(input: &str) -> Result<usize> {
     let almanac: Almanac = input.parse()?;
-    almanac.seed_range_locations().into_iter().map(|r| r.start).min().context("No seed range locations")
+    almanac.seed_range_locations()?.min().context("No seed range locations")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = "seeds: 79 14 55 13
-
-seed-to-soil map:
-50 98 2
-52 50 48
-
-soil-to-fertilizer map:
-0 15 37
-37 52 2
-39 0 15
-
-fertilizer-to-water map:
-49 53 8
-0 11 42
-42 0 7
-57 7 4
-
-water-to-light map:
-88 18 7
-18 25 70
-
-light-to-temperature map:
-45 77 23
-81 45 19
-68 64 13
-
-temperature-to-humidity map:
-0 69 1
-1 0 69
-
-humidity-to-location map:
-60 56 37
-56 93 4
-";
-
+    use crate::examples;
     #[test]
     fn test_parse_almanac() {
         let expected = Almanac {
@@ -243,34 +165,55 @@ humidity-to-location map:
                 Maps(vec![Map { dest: 60, source: 56, length: 37 }, Map { dest: 56, source: 93, length: 4 }]),
             ],
         };
-        assert_eq!(EXAMPLE.parse::<Almanac>().unwrap(), expected);
+        assert_eq!(examples::input(5, "example").parse::<Almanac>().unwrap(), expected);
     }
 
     #[test]
     fn test_seed_location() {
-        assert_eq!(EXAMPLE.parse::<Almanac>().unwrap().seed_location(79), 82);
+        assert_eq!(examples::input(5, "example").parse::<Almanac>().unwrap().seed_location(79), 82);
     }
 
     #[test]
     fn test_seed_locations() {
-        assert_eq!(EXAMPLE.parse::<Almanac>().unwrap().seed_locations(), [82, 43, 86, 35]);
+        assert_eq!(examples::input(5, "example").parse::<Almanac>().unwrap().seed_locations(), [82, 43, 86, 35]);
     }
 
     #[test]
     fn test_seed_ranges() {
-        assert_eq!(EXAMPLE.parse::<Almanac>().unwrap().seed_ranges(), [79..93, 55..68])
-    }
-
-    #[test]
-    fn test_example() {
-        assert_eq!(part1(EXAMPLE).unwrap(), 35);
-        assert_eq!(part2(EXAMPLE).unwrap(), 46);
+        assert_eq!(examples::input(5, "example").parse::<Almanac>().unwrap().seed_ranges().unwrap(), [79..93, 55..68])
     }
 
     #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day05.txt").unwrap();
-        assert_eq!(part1(&input).unwrap(), 111627841);
-        assert_eq!(part2(&input).unwrap(), 69323688);
+    fn test_seed_range_locations_agree_with_seed_location() {
+        crate::generate::check_cases(200, |rng| {
+            // Small enough numbers that every seed in every range can be mapped one at a time
+            let seeds: Vec<usize> = (0..2 * rng.range(1..5))
+                .map(|i| if i % 2 == 0 { rng.range(0..100) } else { rng.range(1..30) })
+                .collect();
+            let maps = (0..7)
+                .map(|_| {
+                    let mut cuts: Vec<usize> = (0..2 * rng.range(0..5)).map(|_| rng.range(0..150)).collect();
+                    cuts.sort_unstable();
+                    cuts.dedup();
+                    let maps = cuts.chunks_exact(2).map(|c| Map {
+                        dest: rng.range(0..150),
+                        source: c[0],
+                        length: c[1] - c[0],
+                    });
+                    Maps(maps.collect())
+                })
+                .collect();
+            let almanac = Almanac { seeds, maps };
+
+            let one_at_a_time: IntervalSet = almanac
+                .seed_ranges()
+                .unwrap()
+                .into_iter()
+                .flatten()
+                .map(|seed| almanac.seed_location(seed))
+                .map(|l| l..l + 1)
+                .collect();
+            assert_eq!(almanac.seed_range_locations().unwrap(), one_at_a_time, "{almanac:?}");
+        });
     }
 }
@@ -1,7 +1,9 @@
 use anyhow::{anyhow, Context, Result};
 
+use crate::parse::Locate;
+
 #[derive(Debug, PartialEq)]
-struct Race {
+pub struct Race {
     time: usize,
     dist: usize,
 }
@@ -15,16 +17,12 @@ impl Race {
         self.distance_will_travel(time_button_held) > self.dist
     }
 
-    #[allow(dead_code)]
+    #[cfg(test)]
     fn brute_force_num_ways_to_win(&self) -> usize {
-        // Approx 1500ms on part2 with puzzle input
         (0..=self.time).filter(|&i| self.wins(i)).count()
     }
 
-    #[allow(dead_code)]
     fn smarter_num_ways_to_win(&self) -> usize {
-        // Approx 150ms on part2 with puzzle input
-        //
         // Since we have a quadratic function, we know that it reaches an optimum at a point
         // By construction of the problem its safe to assume this is a maximum (could also just analyze the function)
         // In simpler math: (where t = race time, and x is the time the button is held)
@@ -97,24 +95,27 @@ impl Race {
     }
 }
 
-fn parse_input(input: &str) -> Result<Vec<Race>> {
+pub fn parse_input(input: &str) -> Result<Vec<Race>> {
     let mut lines = input.lines();
-    let times = lines.next().context("Expected race times")?;
-    let dists = lines.next().context("Expected race dists")?;
+    let times = lines.next().context("Expected race times").at(input)?;
+    let dists = lines.next().context("Expected race dists").at(input)?;
     if let Some(l) = lines.next() {
-        return Err(anyhow!("Expected end of input, but found another line: {l}"));
+        return Err(anyhow!("Expected end of input, but found another line: {l}")).at(l);
     }
 
     let mut times_vec: Vec<usize> = Vec::new();
     let mut dists_vec: Vec<usize> = Vec::new();
-    for time in times.strip_prefix("Time:").context("expected 'Time:' prefix")?.split_ascii_whitespace() {
-        times_vec.push(time.parse()?);
+    for time in times.strip_prefix("Time:").context("expected 'Time:' prefix").at(times)?.split_ascii_whitespace() {
+        times_vec.push(time.parse().at(time)?);
     }
-    for dist in dists.strip_prefix("Distance:").context("expected 'Distance:' prefix")?.split_ascii_whitespace() {
-        dists_vec.push(dist.parse()?);
+    for dist in
+        dists.strip_prefix("Distance:").context("expected 'Distance:' prefix").at(dists)?.split_ascii_whitespace()
+    {
+        dists_vec.push(dist.parse().at(dist)?);
     }
     if times_vec.len() != dists_vec.len() {
-        return Err(anyhow!("Expected equal number of times ({}) and dists ({})", times_vec.len(), dists_vec.len()));
+        return Err(anyhow!("Expected equal number of times ({}) and dists ({})", times_vec.len(), dists_vec.len()))
+            .at(dists);
     }
 
     Ok(times_vec.into_iter().zip(dists_vec).map(|(time, dist)| Race { time, dist }).collect())
@@ -142,15 +143,12 @@ pub fn part2(input: &str) -> Result<usize> {
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    const EXAMPLE: &str = "Time:      7  15   30
-Distance:  9  40  200
-";
+    use crate::examples;
 
     #[test]
     fn test_parse_input() {
         assert_eq!(
-            parse_input(EXAMPLE).unwrap(),
+            parse_input(&examples::input(6, "example")).unwrap(),
             [Race { time: 7, dist: 9 }, Race { time: 15, dist: 40 }, Race { time: 30, dist: 200 }]
         );
     }
@@ -176,15 +174,13 @@ Distance:  9  40  200
     }
 
     #[test]
-    fn test_example() {
-        assert_eq!(part1(EXAMPLE).unwrap(), 288);
-        assert_eq!(part2(EXAMPLE).unwrap(), 71503);
-    }
-
-    #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day06.txt").unwrap();
-        assert_eq!(part1(&input).unwrap(), 131376);
-        assert_eq!(part2(&input).unwrap(), 34123437);
+    fn test_num_ways_to_win_agree() {
+        crate::generate::check_cases(500, |rng| {
+            let time = rng.range(2..5000);
+            let race = Race { time, dist: rng.range(0..(time / 2) * (time - time / 2)) };
+            let brute_force = race.brute_force_num_ways_to_win();
+            assert_eq!(race.smarter_num_ways_to_win(), brute_force, "{race:?}");
+            assert_eq!(race.binary_search_num_ways_to_win(), brute_force, "{race:?}");
+        });
     }
 }
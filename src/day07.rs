@@ -1,6 +1,8 @@
-use anyhow::{anyhow, Error, Result};
+use anyhow::{anyhow, bail, Context, Error, Result};
 use std::{cmp::Ordering, collections::HashMap, str::FromStr};
 
+use crate::parse::Locate;
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Hash)]
 enum Card {
     Two = 2,
@@ -18,9 +20,10 @@ enum Card {
     Ace,
 }
 
-impl From<char> for Card {
-    fn from(value: char) -> Self {
-        match value {
+impl TryFrom<char> for Card {
+    type Error = Error;
+    fn try_from(value: char) -> Result<Self> {
+        Ok(match value {
             '2' => Card::Two,
             '3' => Card::Three,
             '4' => Card::Four,
@@ -34,13 +37,19 @@ impl From<char> for Card {
             'Q' => Card::Queen,
             'K' => Card::King,
             'A' => Card::Ace,
-            _ => panic!("Card can't be {value}"),
-        }
+            _ => bail!("Card can't be {value}"),
+        })
     }
 }
 
+/// The five cards at the start of a hand
+fn parse_cards(cards: &str) -> Result<[Card; 5]> {
+    let cards_vec = cards.chars().map(Card::try_from).collect::<Result<Vec<Card>>>().at(cards)?;
+    cards_vec[..].try_into().context("Expected 5 cards").at(cards)
+}
+
 #[derive(PartialEq, Eq, Debug)]
-struct HandP1 {
+pub struct HandP1 {
     cards: [Card; 5],
     hand_type: HandType,
     bid: usize,
@@ -65,16 +74,16 @@ impl FromStr for HandP1 {
     type Err = Error;
     fn from_str(s: &str) -> Result<Self> {
         if let Some((cards, bid)) = s.split_once(' ') {
-            let cards: [Card; 5] = cards.chars().map(|c| c.into()).collect::<Vec<Card>>()[..].try_into()?;
-            Ok(Self { cards, bid: bid.parse()?, hand_type: HandType::parse_p1(&cards) })
+            let cards = parse_cards(cards)?;
+            Ok(Self { cards, bid: bid.parse().at(bid)?, hand_type: HandType::parse_p1(&cards) })
         } else {
-            Err(anyhow!("Could not split the hand into a (cards, bid) tuple: {s}"))
+            Err(anyhow!("Could not split the hand into a (cards, bid) tuple")).at(s)
         }
     }
 }
 
 #[derive(PartialEq, Eq, Debug)]
-struct HandP2 {
+pub struct HandP2 {
     cards: [Card; 5],
     hand_type: HandType,
     bid: usize,
@@ -113,10 +122,10 @@ impl FromStr for HandP2 {
     type Err = Error;
     fn from_str(s: &str) -> Result<Self> {
         if let Some((cards, bid)) = s.split_once(' ') {
-            let cards: [Card; 5] = cards.chars().map(|c| c.into()).collect::<Vec<Card>>()[..].try_into()?;
-            Ok(Self { cards, bid: bid.parse()?, hand_type: HandType::parse_p2(&cards) })
+            let cards = parse_cards(cards)?;
+            Ok(Self { cards, bid: bid.parse().at(bid)?, hand_type: HandType::parse_p2(&cards) })
         } else {
-            Err(anyhow!("Could not split the hand into a (cards, bid) tuple: {s}"))
+            Err(anyhow!("Could not split the hand into a (cards, bid) tuple")).at(s)
         }
     }
 }
@@ -226,13 +235,6 @@ pub fn part2(input: &str) -> Result<usize> {
 mod tests {
     use super::*;
 
-    const EXAMPLE: &str = "32T3K 765
-T55J5 684
-KK677 28
-KTJJT 220
-QQQJA 483
-";
-
     #[test]
     fn test_hand_p1_from_string() {
         let hand: HandP1 = "32T3K 765".parse().unwrap();
@@ -289,21 +291,4 @@ QQQJA 483
         assert!(h3 > h1);
         assert!(h3 > h2);
     }
-
-    #[test]
-    fn test_example_p1() {
-        assert_eq!(part1(EXAMPLE).unwrap(), 6440);
-    }
-
-    #[test]
-    fn test_example_p2() {
-        assert_eq!(part2(EXAMPLE).unwrap(), 5905);
-    }
-
-    #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day07.txt").unwrap();
-        assert_eq!(part1(&input).unwrap(), 256448566);
-        assert_eq!(part2(&input).unwrap(), 254412181);
-    }
 }
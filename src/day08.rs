@@ -1,24 +1,38 @@
-use anyhow::{Context, Error, Result};
+use anyhow::{anyhow, bail, Context, Error, Result};
 use num::Integer;
-use std::{collections::HashMap, ops::Range, str::FromStr};
+use std::str::FromStr;
+
+use crate::{
+    cycle::{self, chinese_remainder, Cycle},
+    parse::Locate,
+};
 
 #[derive(Eq, PartialEq)]
-enum Instruction {
+pub enum Instruction {
     Left,
     Right,
 }
 
-impl From<char> for Instruction {
-    fn from(value: char) -> Self {
+impl TryFrom<char> for Instruction {
+    type Error = Error;
+    fn try_from(value: char) -> Result<Self> {
         match value {
-            'L' => Self::Left,
-            'R' => Self::Right,
-            _ => panic!("Cannot parse an instruction from {value}"),
+            'L' => Ok(Self::Left),
+            'R' => Ok(Self::Right),
+            _ => bail!("Cannot parse an instruction from {value}"),
         }
     }
 }
 
-struct GraphP1 {
+pub fn parse_instructions(input: &str) -> Result<Vec<Instruction>> {
+    let instructions = input.chars().map(Instruction::try_from).collect::<Result<Vec<_>>>().at(input)?;
+    if instructions.is_empty() {
+        return Err(anyhow!("Expected at least one instruction")).at(input);
+    }
+    Ok(instructions)
+}
+
+pub struct GraphP1 {
     start: usize,
     end: usize,
     edges: Vec<(usize, usize)>,
@@ -73,12 +87,13 @@ fn solve_p1(instructions: &[Instruction], edges: &[(usize, usize)], start: usize
 
 pub fn part1(input: &str) -> Result<usize> {
     let (i, g) = input.split_once("\n\n").context("Could not split instructions and graph")?;
-    let (instructions, graph): (Vec<Instruction>, GraphP1) = (i.chars().map(|c| c.into()).collect(), g.parse()?);
+    let (instructions, graph): (Vec<Instruction>, GraphP1) = (parse_instructions(i)?, g.parse()?);
     Ok(solve_p1(&instructions, &graph.edges, graph.start, graph.end))
 }
 
-struct GraphP2 {
+pub struct GraphP2 {
     start: Vec<usize>,
+    is_end: Vec<bool>,
     edges: Vec<(usize, usize)>,
 }
 
@@ -110,87 +125,54 @@ impl FromStr for GraphP2 {
         }
 
         let start: Vec<usize> = nodes.iter().enumerate().filter(|(_, n)| n.ends_with('A')).map(|(i, _)| i).collect();
+        let is_end: Vec<bool> = nodes.iter().map(|n| n.ends_with('Z')).collect();
 
-        Ok(Self { start, edges })
+        Ok(Self { start, is_end, edges })
     }
 }
 
-fn detect_cycle(instructions: &[Instruction], edges: &[(usize, usize)], start: usize) -> (usize, Range<usize>) {
-    let mut visited: HashMap<(usize, usize), usize> = HashMap::new();
-    let mut posn = start;
-    for step in 0.. {
-        if let Some(first_step) = visited.insert((posn, step % instructions.len()), step) {
-            return (posn, first_step..step);
-        }
-        posn = match instructions[step % instructions.len()] {
-            Instruction::Left => edges[posn].0,
-            Instruction::Right => edges[posn].1,
+/// The cycle that a ghost starting at `start` ends up in, and every step before it first repeats at which it is on an
+/// end
+fn ghost_cycle(instructions: &[Instruction], graph: &GraphP2, start: usize) -> (Cycle, Vec<usize>) {
+    // The ghost's state is its position and which instruction it will follow next
+    let (cycle, states) = cycle::hashed((start, 0), |&(posn, i)| {
+        let next = match instructions[i] {
+            Instruction::Left => graph.edges[posn].0,
+            Instruction::Right => graph.edges[posn].1,
         };
-    }
-    panic!("Reached end of iteration (usize max) without finding a cycle")
+        (next, (i + 1) % instructions.len())
+    });
+    let ends = states.iter().enumerate().filter(|(_, (posn, _))| graph.is_end[*posn]).map(|(step, _)| step).collect();
+    (cycle, ends)
 }
 
 pub fn part2(input: &str) -> Result<usize> {
     let (i, g) = input.split_once("\n\n").context("Could not split instructions and graph")?;
-    let (instructions, graph): (Vec<Instruction>, GraphP2) = (i.chars().map(|c| c.into()).collect(), g.parse()?);
-    let cycles: Vec<(usize, Range<usize>)> =
-        graph.start.iter().map(|&s| detect_cycle(&instructions, &graph.edges, s)).collect();
-    // Bit of a hack: just use the cycle lengths directly
-    // We assume that the lead time to a cycle does not matter
-    // We assume the last node of the cycle is an end
-    // We also assume that this last node will be the one that matters for the solution
-    // (There can be multiple terminal nodes on a cycle, but we ignore this)
-    Ok(cycles.iter().fold(1, |a, b| a.lcm(&b.1.len())))
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "RL
-
-AAA = (BBB, CCC)
-BBB = (DDD, EEE)
-CCC = (ZZZ, GGG)
-DDD = (DDD, DDD)
-EEE = (EEE, EEE)
-GGG = (GGG, GGG)
-ZZZ = (ZZZ, ZZZ)
-";
-    const EXAMPLE_2: &str = "LLR
-
-AAA = (BBB, BBB)
-BBB = (AAA, ZZZ)
-ZZZ = (ZZZ, ZZZ)
-";
-
-    const EXAMPLE_3: &str = "LR
-
-11A = (11B, XXX)
-11B = (XXX, 11Z)
-11Z = (11B, XXX)
-22A = (22B, XXX)
-22B = (22C, 22C)
-22C = (22Z, 22Z)
-22Z = (22B, 22B)
-XXX = (XXX, XXX)
-";
-
-    #[test]
-    fn test_example_p1() {
-        assert_eq!(part1(EXAMPLE).unwrap(), 2);
-        assert_eq!(part1(EXAMPLE_2).unwrap(), 6);
-    }
-
-    #[test]
-    fn test_example_p2() {
-        assert_eq!(part2(EXAMPLE_3).unwrap(), 6);
+    let (instructions, graph): (Vec<Instruction>, GraphP2) = (parse_instructions(i)?, g.parse()?);
+    let ghosts: Vec<(Cycle, Vec<usize>)> = graph.start.iter().map(|&s| ghost_cycle(&instructions, &graph, s)).collect();
+
+    // Until every ghost has reached its cycle, just check each step directly
+    let lead_in = ghosts.iter().map(|(cycle, _)| cycle.tail).max().unwrap_or(0);
+    if let Some(step) = (0..lead_in).find(|&step| ghosts.iter().all(|(cycle, ends)| ends.contains(&cycle.reduce(step))))
+    {
+        return Ok(step);
     }
 
-    #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day08.txt").unwrap();
-        assert_eq!(part1(&input).unwrap(), 21389);
-        assert_eq!(part2(&input).unwrap(), 21083806112641);
+    // After that, each ghost is on an end exactly when the step is congruent to one of its ends on the cycle
+    let mut congruences: Vec<(i128, i128)> = vec![(0, 1)];
+    for (cycle, ends) in &ghosts {
+        let period = cycle.period as i128;
+        let cycle_ends: Vec<i128> = ends.iter().filter(|&&e| e >= cycle.tail).map(|&e| e as i128 % period).collect();
+        congruences = congruences
+            .iter()
+            .flat_map(|&c| cycle_ends.iter().filter_map(move |&e| chinese_remainder(c, (e, period))))
+            .collect();
     }
+    let lead_in = lead_in as i128;
+    congruences
+        .into_iter()
+        .map(|(residue, modulus)| residue + Integer::div_ceil(&(lead_in - residue).max(0), &modulus) * modulus)
+        .min()
+        .map(|step| step as usize)
+        .context("The ghosts are never all on an end at the same time")
 }
@@ -1,11 +1,15 @@
-use std::str::FromStr;
+use std::{collections::HashSet, str::FromStr};
 
-use anyhow::{anyhow, bail, Context, Error, Result};
+use anyhow::{bail, Context, Error, Result};
 use pathfinding::directed::dijkstra::dijkstra_all;
 
-type Posn = (usize, usize);
+use crate::{
+    grid::{Grid, Posn},
+    point::{self, Direction, Point},
+    render::{self, Image, Rgb},
+};
 
-#[derive(PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Tile {
     Vert,
     Hori,
@@ -18,17 +22,21 @@ enum Tile {
 }
 
 impl Tile {
-    fn is_connected_east(&self) -> bool {
-        [Self::Hori, Self::NE, Self::SE, Self::Start].contains(self)
+    /// The pipe that connects in exactly these two directions
+    fn joining(a: Direction, b: Direction) -> Self {
+        [Self::Vert, Self::Hori, Self::NE, Self::NW, Self::SE, Self::SW]
+            .into_iter()
+            .find(|tile| tile.is_connected(a) && tile.is_connected(b))
+            .expect("every pair of directions has a pipe")
     }
-    fn is_connected_west(&self) -> bool {
-        [Self::Hori, Self::NW, Self::SW, Self::Start].contains(self)
-    }
-    fn is_connected_north(&self) -> bool {
-        [Self::Vert, Self::NE, Self::NW, Self::Start].contains(self)
-    }
-    fn is_connected_south(&self) -> bool {
-        [Self::Vert, Self::SE, Self::SW, Self::Start].contains(self)
+
+    fn is_connected(&self, direction: Direction) -> bool {
+        match direction {
+            Direction::East => [Self::Hori, Self::NE, Self::SE, Self::Start].contains(self),
+            Direction::West => [Self::Hori, Self::NW, Self::SW, Self::Start].contains(self),
+            Direction::North => [Self::Vert, Self::NE, Self::NW, Self::Start].contains(self),
+            Direction::South => [Self::Vert, Self::SE, Self::SW, Self::Start].contains(self),
+        }
     }
 }
 
@@ -49,50 +57,75 @@ impl TryFrom<char> for Tile {
     }
 }
 
-struct Matrix(Vec<Vec<Tile>>);
+pub struct Matrix(Grid<Tile>);
 
 impl FromStr for Matrix {
     type Err = Error;
     fn from_str(input: &str) -> Result<Self> {
-        let mut matrix: Vec<Vec<Tile>> = Vec::new();
-        for l in input.lines() {
-            let mut row: Vec<Tile> = Vec::new();
-            for c in l.chars() {
-                row.push(c.try_into()?);
-            }
-            matrix.push(row);
-        }
-        Ok(Self(matrix))
+        Ok(Self(input.parse()?))
     }
 }
 
 impl Matrix {
     fn start(&self) -> Result<Posn> {
-        for (i, row) in self.0.iter().enumerate() {
-            for (j, tile) in row.iter().enumerate() {
-                if tile == &Tile::Start {
-                    return Ok((i, j));
+        self.0.position(|tile| tile == &Tile::Start).context("Could not find the start position")
+    }
+
+    fn neighbours(&self, posn: Posn) -> Vec<Posn> {
+        Direction::ALL
+            .into_iter()
+            .filter(|&direction| self.0[posn].is_connected(direction))
+            .filter_map(|direction| {
+                self.0.step(posn, direction).filter(|&next| self.0[next].is_connected(direction.reverse()))
+            })
+            .collect()
+    }
+
+    /// The pipe under the start tile and the tiles of the loop through it, in order, ignoring any other pipes. Any
+    /// two of the pipes around the start that connect back to it could be the loop, so this tries each pair in turn.
+    fn start_loop(&self) -> Result<(Tile, Vec<Posn>)> {
+        let start = self.start()?;
+        let connected: Vec<Direction> = Direction::ALL
+            .into_iter()
+            .filter(|&direction| {
+                self.0
+                    .step(start, direction)
+                    .is_some_and(|next| self.0[next] != Tile::Start && self.0[next].is_connected(direction.reverse()))
+            })
+            .collect();
+        for (i, &a) in connected.iter().enumerate() {
+            for &b in &connected[i + 1..] {
+                let start_tile = Tile::joining(a, b);
+                if let Some(pipe_loop) = self.follow(start, start_tile) {
+                    return Ok((start_tile, pipe_loop));
                 }
             }
         }
-        Err(anyhow!("Could not find the start position"))
+        bail!("None of the {} pipes that connect to the start lead back to it", connected.len())
     }
 
-    fn neighbours(&self, (i, j): Posn) -> Vec<Posn> {
-        let mut adjacent: Vec<Posn> = Vec::new();
-        if self.0[i][j].is_connected_north() && i != 0 && self.0[i - 1][j].is_connected_south() {
-            adjacent.push((i - 1, j));
-        }
-        if self.0[i][j].is_connected_east() && self.0[i].get(j + 1).is_some_and(|t| t.is_connected_west()) {
-            adjacent.push((i, j + 1));
-        }
-        if self.0[i][j].is_connected_south() && self.0.get(i + 1).is_some_and(|r| r[j].is_connected_north()) {
-            adjacent.push((i + 1, j));
-        }
-        if self.0[i][j].is_connected_west() && j != 0 && self.0[i][j - 1].is_connected_east() {
-            adjacent.push((i, j - 1));
+    /// The tiles of the loop through the start, in order
+    fn pipe_loop(&self) -> Result<Vec<Posn>> {
+        Ok(self.start_loop()?.1)
+    }
+
+    /// The tiles passed through by following the pipes from the start, if they lead back to it
+    fn follow(&self, start: Posn, start_tile: Tile) -> Option<Vec<Posn>> {
+        let tile = |posn: Posn| if posn == start { start_tile } else { self.0[posn] };
+        let mut direction = Direction::ALL.into_iter().find(|&d| start_tile.is_connected(d)).expect("start is a pipe");
+        let mut pipe_loop = vec![start];
+        let mut posn = start;
+        loop {
+            posn = self.0.step(posn, direction).filter(|&next| tile(next).is_connected(direction.reverse()))?;
+            if posn == start {
+                return Some(pipe_loop);
+            }
+            pipe_loop.push(posn);
+            direction = Direction::ALL
+                .into_iter()
+                .find(|&d| d != direction.reverse() && tile(posn).is_connected(d))
+                .expect("every pipe has two connections");
         }
-        adjacent
     }
 }
 
@@ -102,169 +135,82 @@ pub fn part1(input: &str) -> Result<usize> {
     shortest_paths.iter().map(|(_node, (_previous, length))| *length as usize).max().context("No paths from start")
 }
 
-fn is_boundary_transition(a: &Tile, b: &Tile) -> bool {
-    match b {
-        Tile::Ground => panic!("Should not be getting a ground"),
-        Tile::Start | Tile::Hori | Tile::NW | Tile::SW => match a {
-            Tile::Ground | Tile::Vert | Tile::NW | Tile::SW => true,
-            _ => false,
-        },
-        Tile::Vert | Tile::NE | Tile::SE => true,
-    }
-}
-
-pub fn part2(input: &str) -> Result<usize> {
-    let matrix: Matrix = input.parse()?;
-
-    let mut inside_tiles = 0;
-
-    // Using a ray casing method
-    // Use diagonal rays so we don't have to worry about edges
-
-    // Start with rays starting on the left side, going up at 45 degrees
-    for ray_start in 0..matrix.0.len() {
-        let mut inside: bool = false;
-        for offset in 0.. {
-            if offset > ray_start {
-                break;
-            }
-            let row = &matrix.0[ray_start - offset];
-            if offset >= row.len() {
-                break;
-            }
-            match &row[offset] {
-                Tile::Ground => {
-                    if inside {
-                        inside_tiles += 1;
-                    }
+/// The tiles inside the loop, found by casting diagonal rays up and to the right so that a ray never runs along a
+/// pipe. A ray crosses the loop at every loop tile except the F and J corners, which it only touches.
+fn inside_tiles(matrix: &Matrix) -> Result<Vec<Posn>> {
+    let start = matrix.start()?;
+    let (start_tile, pipe_loop) = matrix.start_loop()?;
+    let pipe_loop: HashSet<Posn> = pipe_loop.into_iter().collect();
+    let (height, width) = (matrix.0.height(), matrix.0.width());
+
+    let mut inside_tiles: Vec<Posn> = Vec::new();
+    for diagonal in 0..(height + width).saturating_sub(1) {
+        let mut inside = false;
+        for i in (diagonal.saturating_sub(width - 1)..height.min(diagonal + 1)).rev() {
+            let posn = (i, diagonal - i);
+            if !pipe_loop.contains(&posn) {
+                if inside {
+                    inside_tiles.push(posn);
                 }
-                _ => inside = !inside,
-            }
-        }
-    }
-
-    // Now the bottom edge, with care not to duplicate the corner case
-    for ray_start in 1..matrix.0[matrix.0.len() - 1].len() {
-        let mut inside: bool = false;
-        for offset in 0.. {
-            if offset >= matrix.0.len() {
-                break;
-            }
-            let row = &matrix.0[matrix.0.len() - 1 - offset];
-            if ray_start + offset >= row.len() {
-                break;
+                continue;
             }
-            match &row[ray_start + offset] {
-                Tile::Ground => {
-                    if inside {
-                        inside_tiles += 1;
-                    }
-                }
-                _ => inside = !inside,
+            let tile = if posn == start { start_tile } else { matrix.0[posn] };
+            if !matches!(tile, Tile::SE | Tile::NW) {
+                inside = !inside;
             }
         }
     }
-
     Ok(inside_tiles)
 }
 
+pub fn part2(input: &str) -> Result<usize> {
+    let matrix: Matrix = input.parse()?;
+    let corners: Vec<Point> = matrix.pipe_loop()?.into_iter().map(Point::from).collect();
+    point::interior_points(&corners).context("The pipe loop crosses or doubles back on itself")
+}
+
+/// The pipe loop in white, other pipes in grey, and the tiles that part 2 counts as inside the loop in green
+pub fn render(input: &str) -> Result<Image> {
+    let matrix: Matrix = input.parse()?;
+    let mut image = matrix.0.map(|tile| if tile == &Tile::Ground { Rgb::BLACK } else { Rgb::DARK_GREY });
+    let start = matrix.start()?;
+    render::overlay(&mut image, matrix.pipe_loop()?, Rgb::WHITE);
+    render::overlay(&mut image, [start], Rgb::RED);
+    render::overlay(&mut image, inside_tiles(&matrix)?, Rgb::GREEN);
+    Ok(image)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    const EXAMPLE: &str = ".....
-.S-7.
-.|.|.
-.L-J.
-.....
-";
-
-    const EXAMPLE_WITH_CRUD: &str = "-L|F7
-7S-7|
-L|7||
--L-J|
-L|-JF
-";
-
-    const COMPLEX_EXAMPLE: &str = "..F7.
-.FJ|.
-SJ.L7
-|F--J
-LJ...
-";
-
-    const COMPLEX_EXAMPLE_WITH_CRUD: &str = "7-F7-
-.FJ|7
-SJLL7
-|F--J
-LJ.LJ
-";
-
-    const P2_EXAMPLE: &str = "...........
-.S-------7.
-.|F-----7|.
-.||.....||.
-.||.....||.
-.|L-7.F-J|.
-.|..|.|..|.
-.L--J.L--J.
-...........
-";
-
-    const P2_EXAMPLE_CLOSED: &str = "..........
-.S------7.
-.|F----7|.
-.||....||.
-.||....||.
-.|L-7F-J|.
-.|..||..|.
-.L--JL--J.
-..........
-";
-
-    const LARGER_EXAMPLE: &str = ".F----7F7F7F7F-7....
-.|F--7||||||||FJ....
-.||.FJ||||||||L7....
-FJL7L7LJLJ||LJ.L-7..
-L--J.L7...LJS7F-7L7.
-....F-J..F7FJ|L7L7L7
-....L7.F7||L7|.L7L7|
-.....|FJLJ|FJ|F7|.LJ
-....FJL-7.||.||||...
-....L---J.LJ.LJLJ...
-";
-
-    const LARGER_EXAMPLE_WITH_CRUD: &str = "FF7FSF7F7F7F7F7F---7
-L|LJ||||||||||||F--J
-FL-7LJLJ||||||LJL-77
-F--JF--7||LJLJ7F7FJ-
-L---JF-JLJ.||-FJLJJ7
-|F|F-JF---7F7-L7L|7|
-|FFJF7L7F-JF7|JL---7
-7-L-JL7||F7|L7F-7F7|
-L.L7LFJ|||||FJL7||LJ
-L7JLJL-JLJLJL--JLJ.L
-";
+    use crate::examples;
 
     #[test]
-    fn test_example_part1() {
-        assert_eq!(part1(EXAMPLE).unwrap(), 4);
-        assert_eq!(part1(EXAMPLE_WITH_CRUD).unwrap(), 4);
-        assert_eq!(part1(COMPLEX_EXAMPLE).unwrap(), 8);
-        assert_eq!(part1(COMPLEX_EXAMPLE_WITH_CRUD).unwrap(), 8);
+    fn test_start_tile() {
+        let matrix: Matrix = examples::input(10, "complex_example_with_crud").parse().unwrap();
+        assert_eq!(matrix.start_loop().unwrap().0, Tile::SE);
+        assert_eq!(matrix.pipe_loop().unwrap().len(), 16);
     }
 
     #[test]
-    fn test_example_part2() {
-        assert_eq!(part2(P2_EXAMPLE).unwrap(), 4);
-        assert_eq!(part2(P2_EXAMPLE_CLOSED).unwrap(), 4);
-        // assert_eq!(part2(LARGER_EXAMPLE).unwrap(), 10);
-        // assert_eq!(part2(LARGER_EXAMPLE_WITH_CRUD).unwrap(), 10);
+    fn test_start_tile_ignores_pipes_off_the_loop() {
+        // The pipe to the left of the start points at it, but doesn't lead back round
+        let matrix: Matrix = "-S-7\n.|.|\n.L-J\n".parse().unwrap();
+        assert_eq!(matrix.start_loop().unwrap().0, Tile::SE);
+        assert_eq!(matrix.pipe_loop().unwrap().len(), 8);
+        let matrix: Matrix = "-S-.\n.|..\n.L-J\n".parse().unwrap();
+        assert!(matrix.pipe_loop().is_err());
     }
 
     #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day10.txt").unwrap();
-        assert_eq!(part1(&input).unwrap(), 6714);
+    fn test_ray_casting_agrees_with_picks_theorem() {
+        for name in ["enclosed", "enclosed_closed", "larger_example", "larger_example_with_crud"] {
+            let input = examples::input(10, name);
+            assert_eq!(inside_tiles(&input.parse().unwrap()).unwrap().len(), part2(&input).unwrap(), "{name}");
+        }
+        crate::generate::check_cases(50, |rng| {
+            let input = crate::generate::input(10, rng.next_u64(), 30).unwrap();
+            assert_eq!(inside_tiles(&input.parse().unwrap()).unwrap().len(), part2(&input).unwrap(), "{input}");
+        });
     }
 }
@@ -1,7 +1,10 @@
 use anyhow::{anyhow, Context, Error, Result};
+use std::collections::HashMap;
 
-#[derive(Eq, PartialEq)]
-enum Condition {
+use crate::parse::Locate;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Condition {
     Operational,
     Damaged,
     Unknown,
@@ -23,8 +26,8 @@ fn parse_spring_conditions(input: &str) -> Result<Vec<(Condition, usize)>> {
     let mut spring_conditions: Vec<(Condition, usize)> = Vec::new();
     let mut current_condition: Option<Condition> = None;
     let mut current_count: usize = 0;
-    for condition in input.chars() {
-        let condition: Condition = condition.try_into()?;
+    for (i, c) in input.char_indices() {
+        let condition: Condition = c.try_into().at(&input[i..i + c.len_utf8()])?;
         match current_condition {
             Some(ref cc) if cc == &condition => current_count += 1,
             Some(cc) => {
@@ -47,20 +50,46 @@ fn parse_spring_conditions(input: &str) -> Result<Vec<(Condition, usize)>> {
 fn parse_damaged_info(input: &str) -> Result<Vec<usize>> {
     let mut damaged_info: Vec<usize> = Vec::new();
     for c in input.split(',') {
-        damaged_info.push(c.parse()?);
+        damaged_info.push(c.parse().at(c)?);
     }
     Ok(damaged_info)
 }
 
-fn parse_input(input: &str) -> Result<Vec<(Vec<(Condition, usize)>, Vec<usize>)>> {
-    let mut spring_info: Vec<(Vec<(Condition, usize)>, Vec<usize>)> = Vec::new();
-    for l in input.lines() {
-        let (conditions, damaged) = l.split_once(' ').context("Could not split line {l}")?;
-        spring_info.push((parse_spring_conditions(conditions)?, parse_damaged_info(damaged)?));
+pub type SpringRecord = (Vec<(Condition, usize)>, Vec<usize>);
+
+fn parse_record(line: &str) -> Result<SpringRecord> {
+    let (conditions, damaged) = line.split_once(' ').context("Expected conditions and damaged groups").at(line)?;
+    Ok((parse_spring_conditions(conditions)?, parse_damaged_info(damaged)?))
+}
+
+pub fn parse_input(input: &str) -> Result<Vec<SpringRecord>> {
+    input.lines().map(parse_record).collect()
+}
+
+/// Five copies of the conditions separated by unknowns, and five copies of the damaged groups
+fn unfold((conditions, damaged): &SpringRecord) -> SpringRecord {
+    let mut unfolded: Vec<(Condition, usize)> = Vec::new();
+    for (i, &(condition, count)) in conditions.iter().cycle().take(conditions.len() * 5).enumerate() {
+        if i > 0 && i % conditions.len() == 0 {
+            push_run(&mut unfolded, Condition::Unknown, 1);
+        }
+        push_run(&mut unfolded, condition, count);
+    }
+    if conditions.is_empty() {
+        unfolded.push((Condition::Unknown, 4));
+    }
+    (unfolded, damaged.repeat(5))
+}
+
+/// Adds a run of springs, joining it on to the last run if they are in the same condition
+fn push_run(conditions: &mut Vec<(Condition, usize)>, condition: Condition, count: usize) {
+    match conditions.last_mut() {
+        Some((last, last_count)) if *last == condition => *last_count += count,
+        _ => conditions.push((condition, count)),
     }
-    Ok(spring_info)
 }
 
+#[cfg(test)]
 fn is_valid_arrangment(conditions: &[(Condition, usize)], damaged: &[usize]) -> bool {
     let mut previous_condition: Option<&Condition> = None;
     let mut damaged_idx = 0;
@@ -79,28 +108,120 @@ fn is_valid_arrangment(conditions: &[(Condition, usize)], damaged: &[usize]) ->
     damaged_idx == damaged.len()
 }
 
+/// Whether a group of `size` damaged springs can start `skip` springs into the first run, with an operational
+/// spring (or the end of the row) after it
+fn fits(conditions: &[(Condition, usize)], mut skip: usize, size: usize) -> bool {
+    let mut needed = size;
+    for (condition, length) in conditions {
+        let available = length - std::mem::take(&mut skip);
+        if needed == 0 {
+            return *condition != Condition::Damaged;
+        }
+        if *condition == Condition::Operational {
+            return false;
+        }
+        if available > needed {
+            return *condition == Condition::Unknown;
+        }
+        needed -= available;
+    }
+    needed == 0
+}
+
+/// Counts the arrangements of the remaining damaged groups, starting `skip` springs into the first run.
+/// Every call is for a suffix of the conditions and groups, so their lengths identify it in the memo.
+fn count(
+    conditions: &[(Condition, usize)],
+    skip: usize,
+    damaged: &[usize],
+    memo: &mut HashMap<(usize, usize, usize), usize>,
+) -> usize {
+    let Some(((condition, length), rest)) = conditions.split_first() else {
+        return usize::from(damaged.is_empty());
+    };
+    if skip >= *length {
+        return count(rest, skip - length, damaged, memo);
+    }
+    let key = (conditions.len(), skip, damaged.len());
+    if let Some(&arrangements) = memo.get(&key) {
+        return arrangements;
+    }
+
+    let mut arrangements = match condition {
+        Condition::Operational => count(rest, 0, damaged, memo),
+        Condition::Unknown => count(conditions, skip + 1, damaged, memo),
+        Condition::Damaged => 0,
+    };
+    if let Some((&size, groups)) = damaged.split_first() {
+        if *condition != Condition::Operational && fits(conditions, skip, size) {
+            // Skipping the operational spring after the group too
+            arrangements += count(conditions, skip + size + 1, groups, memo);
+        }
+    }
+    memo.insert(key, arrangements);
+    arrangements
+}
+
 fn possible_arrangements(conditions: &[(Condition, usize)], damaged: &[usize]) -> usize {
-    todo!()
+    count(conditions, 0, damaged, &mut HashMap::new())
 }
 
 pub fn part1(input: &str) -> Result<usize> {
-    Ok(parse_input(input)?.into_iter().map(|(c, d)| possible_arrangements(&c, &d)).sum())
+    Ok(parse_input(input)?.iter().map(|(c, d)| possible_arrangements(c, d)).sum())
+}
+
+pub fn part2(input: &str) -> Result<usize> {
+    Ok(parse_input(input)?.iter().map(unfold).map(|(c, d)| possible_arrangements(&c, &d)).sum())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::examples;
+
+    /// Tries every way of filling in the unknown springs
+    fn brute_force_arrangements(row: &str, damaged: &[usize]) -> usize {
+        let unknowns: Vec<usize> = row.char_indices().filter(|(_, c)| *c == '?').map(|(i, _)| i).collect();
+        (0..1_usize << unknowns.len())
+            .filter(|assignment| {
+                let mut filled: Vec<char> = row.chars().collect();
+                for (bit, &i) in unknowns.iter().enumerate() {
+                    filled[i] = if assignment & (1 << bit) != 0 { '#' } else { '.' };
+                }
+                let conditions = parse_spring_conditions(&filled.into_iter().collect::<String>()).unwrap();
+                is_valid_arrangment(&conditions, damaged)
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_possible_arrangements() {
+        let counts: Vec<usize> = parse_input(&examples::input(12, "example"))
+            .unwrap()
+            .iter()
+            .map(|(c, d)| possible_arrangements(c, d))
+            .collect();
+        assert_eq!(counts, [1, 4, 1, 1, 4, 10]);
+    }
 
-    const EXAMPLE: &str = "???.### 1,1,3
-.??..??...?##. 1,1,3
-?#?#?#?#?#?#?#? 1,3,1,6
-????.#...#... 4,1,1
-????.######..#####. 1,6,5
-?###???????? 3,2,1
-";
+    #[test]
+    fn test_possible_arrangements_agree_with_brute_force() {
+        crate::generate::check_cases(300, |rng| {
+            let row: String = (0..rng.range(1..13)).map(|_| *rng.choose(&['.', '#', '?'])).collect();
+            let damaged: Vec<usize> = (0..rng.range(1..4)).map(|_| rng.range(1..4)).collect();
+            let (conditions, _) = parse_record(&format!("{row} 1")).unwrap();
+            assert_eq!(
+                possible_arrangements(&conditions, &damaged),
+                brute_force_arrangements(&row, &damaged),
+                "{row} {damaged:?}"
+            );
+        });
+    }
 
     #[test]
-    fn test_example() {
-        // assert_eq!(part1(EXAMPLE).unwrap(), 21);
+    fn test_unfold() {
+        assert_eq!(unfold(&parse_record(".# 1").unwrap()), parse_record(".#?.#?.#?.#?.# 1,1,1,1,1").unwrap());
+        assert_eq!(unfold(&parse_record("?#. 2").unwrap()), parse_record("?#.??#.??#.??#.??#. 2,2,2,2,2").unwrap());
+        assert_eq!(unfold(&parse_record(" 1").unwrap()), parse_record("???? 1,1,1,1,1").unwrap());
     }
 }
@@ -2,16 +2,18 @@ use std::str::FromStr;
 
 use anyhow::{anyhow, Error, Result};
 
-struct Pattern(Vec<Vec<bool>>);
+use crate::grid::Grid;
+
+pub struct Pattern(Grid<bool>);
 
 impl FromStr for Pattern {
     type Err = Error;
     fn from_str(s: &str) -> Result<Self> {
-        Ok(Self(s.lines().map(|l| l.chars().map(|c| c == '#').collect()).collect()))
+        Ok(Self(Grid::parse_with(s, |c| Ok::<_, Error>(c == '#'))?))
     }
 }
 
-fn parse_input(input: &str) -> Result<Vec<Pattern>> {
+pub fn parse_input(input: &str) -> Result<Vec<Pattern>> {
     let mut patterns: Vec<Pattern> = Vec::new();
     for p in input.split("\n\n") {
         patterns.push(p.parse()?);
@@ -20,22 +22,14 @@ fn parse_input(input: &str) -> Result<Vec<Pattern>> {
 }
 
 impl Pattern {
-    fn height(&self) -> usize {
-        self.0.len()
-    }
-
-    fn width(&self) -> usize {
-        self.0[0].len()
-    }
-
     fn value(&self, changes: usize) -> Result<usize> {
-        for i in 1..self.width() {
+        for i in 1..self.0.width() {
             if self.required_changes_for_vertical_line_of_reflection(i) == changes {
                 return Ok(i);
             }
         }
 
-        for i in 1..self.height() {
+        for i in 1..self.0.height() {
             if self.required_changes_for_horizontal_line_of_reflection(i) == changes {
                 return Ok(100 * i);
             }
@@ -49,12 +43,8 @@ impl Pattern {
 
         let mut left_idx = right_idx - 1; // Assume that right_idx >= 1
         loop {
-            for row in self.0.iter() {
-                if row[left_idx] != row[right_idx] {
-                    mismatches += 1;
-                }
-            }
-            if left_idx == 0 || right_idx == self.width() - 1 {
+            mismatches += self.0.column(left_idx).zip(self.0.column(right_idx)).filter(|(a, b)| a != b).count();
+            if left_idx == 0 || right_idx == self.0.width() - 1 {
                 return mismatches;
             }
             left_idx -= 1;
@@ -67,8 +57,8 @@ impl Pattern {
 
         let mut up_idx = down_idx - 1; // Assume that down_idx >= 1
         loop {
-            mismatches += self.0[up_idx].iter().zip(self.0[down_idx].iter()).filter(|(a, b)| a != b).count();
-            if up_idx == 0 || down_idx == self.height() - 1 {
+            mismatches += self.0.row(up_idx).iter().zip(self.0.row(down_idx)).filter(|(a, b)| a != b).count();
+            if up_idx == 0 || down_idx == self.0.height() - 1 {
                 return mismatches;
             }
             up_idx -= 1;
@@ -92,38 +82,3 @@ pub fn part2(input: &str) -> Result<usize> {
     }
     Ok(total)
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "#.##..##.
-..#.##.#.
-##......#
-##......#
-..#.##.#.
-..##..##.
-#.#.##.#.
-
-#...##..#
-#....#..#
-..##..###
-#####.##.
-#####.##.
-..##..###
-#....#..#
-";
-
-    #[test]
-    fn test_example() {
-        assert_eq!(part1(EXAMPLE).unwrap(), 405);
-        assert_eq!(part2(EXAMPLE).unwrap(), 400);
-    }
-
-    #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day13.txt").unwrap();
-        assert_eq!(part1(&input).unwrap(), 39939);
-        assert_eq!(part2(&input).unwrap(), 32069);
-    }
-}
@@ -1,7 +1,9 @@
 use anyhow::{bail, Error, Result};
-use std::{collections::HashMap, str::FromStr};
+use std::str::FromStr;
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+use crate::{cycle, grid::Grid, point::Direction, render::Rgb, visualize::Frame};
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 enum Rock {
     Round,
     Square,
@@ -20,35 +22,37 @@ impl TryFrom<char> for Rock {
     }
 }
 
-#[derive(Default, Eq, PartialEq, Debug)]
-struct Platform(Vec<Vec<Rock>>);
+impl From<Rock> for char {
+    fn from(value: Rock) -> Self {
+        match value {
+            Rock::None => '.',
+            Rock::Round => 'O',
+            Rock::Square => '#',
+        }
+    }
+}
+
+#[derive(Default, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Platform(Grid<Rock>);
 
 impl FromStr for Platform {
     type Err = Error;
     fn from_str(s: &str) -> Result<Self> {
-        let mut platform = Self::default();
-        for l in s.lines() {
-            let mut row: Vec<Rock> = Vec::new();
-            for c in l.chars() {
-                row.push(c.try_into()?);
-            }
-            platform.0.push(row);
-        }
-        Ok(platform)
+        Ok(Self(s.parse()?))
     }
 }
 
 impl Platform {
     fn tilt_north(mut self) -> Self {
-        for j in 0..self.0[0].len() {
+        for j in 0..self.0.width() {
             let mut next_available_slot = 0;
-            for i in 0..self.0.len() {
-                match self.0[i][j] {
+            for i in 0..self.0.height() {
+                match self.0[(i, j)] {
                     Rock::None => (),
                     Rock::Square => next_available_slot = i + 1,
                     Rock::Round => {
-                        self.0[i][j] = Rock::None;
-                        self.0[next_available_slot][j] = Rock::Round;
+                        self.0[(i, j)] = Rock::None;
+                        self.0[(next_available_slot, j)] = Rock::Round;
                         next_available_slot += 1;
                     }
                 }
@@ -57,84 +61,45 @@ impl Platform {
         self
     }
 
-    fn tilt_south(mut self) -> Self {
-        for j in 0..self.0[0].len() {
-            let mut next_available_slot = self.0[0].len() - 1;
-            for i in (0..self.0.len()).rev() {
-                match self.0[i][j] {
-                    Rock::None => (),
-                    Rock::Square => next_available_slot = i.saturating_sub(1),
-                    Rock::Round => {
-                        self.0[i][j] = Rock::None;
-                        self.0[next_available_slot][j] = Rock::Round;
-                        next_available_slot = next_available_slot.saturating_sub(1);
-                    }
-                }
-            }
-        }
-        self
-    }
-
-    fn tilt_west(mut self) -> Self {
-        for row in &mut self.0 {
-            let mut next_available_slot = 0;
-            for i in 0..row.len() {
-                match row[i] {
-                    Rock::None => (),
-                    Rock::Square => next_available_slot = i + 1,
-                    Rock::Round => {
-                        row[i] = Rock::None;
-                        row[next_available_slot] = Rock::Round;
-                        next_available_slot += 1;
-                    }
-                }
-            }
+    fn tilt_cycle(mut self) -> Self {
+        // Rotating clockwise after each tilt brings west, then south, then east round to the north
+        for _ in 0..4 {
+            self = Self(self.tilt_north().0.rotate_clockwise());
         }
         self
     }
 
-    fn tilt_east(mut self) -> Self {
-        for row in &mut self.0 {
-            let mut next_available_slot = row.len() - 1;
-            for i in (0..row.len()).rev() {
-                match row[i] {
-                    Rock::None => (),
-                    Rock::Square => next_available_slot = i.saturating_sub(1),
-                    Rock::Round => {
-                        row[i] = Rock::None;
-                        row[next_available_slot] = Rock::Round;
-                        next_available_slot = next_available_slot.saturating_sub(1);
-                    }
-                }
-            }
+    /// The same as `tilt_cycle`, but calls `on_tilt` after each tilt with the platform turned back the right way up
+    pub fn tilt_cycle_with(mut self, mut on_tilt: impl FnMut(Direction, &Platform)) -> Self {
+        let directions = [Direction::North, Direction::West, Direction::South, Direction::East];
+        for (turns, direction) in directions.into_iter().enumerate() {
+            self = self.tilt_north();
+            let upright = (0..turns).fold(self.0.clone(), |grid, _| grid.rotate_anticlockwise());
+            on_tilt(direction, &Self(upright));
+            self = Self(self.0.rotate_clockwise());
         }
         self
     }
 
-    fn tilt_cycle(self) -> Self {
-        self.tilt_north().tilt_west().tilt_south().tilt_east()
-    }
-
     fn load_on_north_support_beams(&self) -> usize {
         let mut total_value = 0;
-        for (i, row) in self.0.iter().enumerate() {
-            let row_value = row.iter().filter(|&r| r == &Rock::Round).count() * (self.0.len() - i);
+        for (i, row) in self.0.rows().enumerate() {
+            let row_value = row.iter().filter(|&r| r == &Rock::Round).count() * (self.0.height() - i);
             total_value += row_value;
         }
         total_value
     }
 
-    fn identity(&self) -> Vec<(usize, usize)> {
-        // Returns a (sorted) vector of round rocks. This is the relevant piece of cycle detection
-        let mut round_rocks: Vec<(usize, usize)> = Vec::new();
-        for (i, row) in self.0.iter().enumerate() {
-            for (j, r) in row.iter().enumerate() {
-                if r == &Rock::Round {
-                    round_rocks.push((i, j));
-                }
-            }
-        }
-        round_rocks
+    fn frame(&self, caption: String) -> Frame {
+        let cells = self.0.map(|&rock| {
+            let colour = match rock {
+                Rock::Round => Rgb::YELLOW,
+                Rock::Square => Rgb::LIGHT_GREY,
+                Rock::None => Rgb::DARK_GREY,
+            };
+            (char::from(rock), colour)
+        });
+        Frame { cells, caption: format!("{caption}, load {}", self.load_on_north_support_beams()) }
     }
 }
 
@@ -142,39 +107,39 @@ pub fn part1(input: &str) -> Result<usize> {
     Ok(input.parse::<Platform>()?.tilt_north().load_on_north_support_beams())
 }
 
+const NUM_ITERATIONS: usize = 1000000000;
+
 pub fn part2(input: &str) -> Result<usize> {
-    let mut platform: Platform = input.parse()?;
-    let mut visited: HashMap<Vec<(usize, usize)>, usize> = HashMap::default();
-    const NUM_ITERATIONS: usize = 1000000000;
-
-    for i in 0..NUM_ITERATIONS {
-        if let Some(visited_before) = visited.insert(platform.identity(), i) {
-            // There is a cycle: compute the final platform based on the remaining iterations after cycling
-            for _ in 0..((NUM_ITERATIONS - visited_before) % (i - visited_before)) {
-                platform = platform.tilt_cycle();
-            }
-            break;
-        }
-        platform = platform.tilt_cycle();
+    let (cycle, platforms) = cycle::hashed(input.parse::<Platform>()?, |p| p.clone().tilt_cycle());
+    Ok(platforms[cycle.reduce(NUM_ITERATIONS)].load_on_north_support_beams())
+}
+
+/// Part 1's single tilt, or part 2's spin cycles up to the point where they start repeating
+pub fn visualize(input: &str, part: u8, on_frame: &mut dyn FnMut(Frame)) -> Result<()> {
+    let platform: Platform = input.parse()?;
+    on_frame(platform.frame("Before tilting".to_string()));
+    if part == 1 {
+        on_frame(platform.tilt_north().frame("Tilted North".to_string()));
+        return Ok(());
     }
-    Ok(platform.load_on_north_support_beams())
+
+    let mut cycles = 0;
+    let (cycle, platforms) = cycle::hashed(platform, |p| {
+        cycles += 1;
+        p.clone().tilt_cycle_with(|direction, p| on_frame(p.frame(format!("Cycle {cycles}, tilted {direction:?}"))))
+    });
+    let last = cycle.reduce(NUM_ITERATIONS);
+    on_frame(platforms[last].frame(format!(
+        "The cycles repeat every {} from cycle {}, so cycle {NUM_ITERATIONS} looks like cycle {last}",
+        cycle.period, cycle.tail
+    )));
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    const EXAMPLE: &str = "O....#....
-O.OO#....#
-.....##...
-OO.#O....O
-.O.....O#.
-O.#..O.#.#
-..O..#O..O
-.......O..
-#....###..
-#OO..#....
-";
+    use crate::examples;
 
     const EXAMPLE_TILTED_NORTH: &str = "OOOO.#.O..
 OO..#....#
@@ -202,27 +167,24 @@ O..#.OO...
 
     #[test]
     fn test_tilt_example_north() {
-        let platform: Platform = EXAMPLE.parse().unwrap();
+        let platform: Platform = examples::input(14, "example").parse().unwrap();
         let platform_tilted_north: Platform = EXAMPLE_TILTED_NORTH.parse().unwrap();
         assert_eq!(platform.tilt_north(), platform_tilted_north);
     }
 
     #[test]
     fn test_tilt_cycle() {
-        let platform: Platform = EXAMPLE.parse().unwrap();
+        let platform: Platform = examples::input(14, "example").parse().unwrap();
         assert_eq!(platform.tilt_cycle(), EXAMPLE_TILT_CYCLE_1.parse().unwrap());
     }
 
     #[test]
-    fn test_example() {
-        assert_eq!(part1(EXAMPLE).unwrap(), 136);
-        assert_eq!(part2(EXAMPLE).unwrap(), 64);
-    }
-
-    #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day14.txt").unwrap();
-        assert_eq!(part1(&input).unwrap(), 108759);
-        assert_eq!(part2(&input).unwrap(), 89089);
+    fn test_tilt_cycle_with() {
+        let platform: Platform = examples::input(14, "example").parse().unwrap();
+        let mut tilts: Vec<(Direction, Platform)> = Vec::new();
+        let cycled = platform.clone().tilt_cycle_with(|direction, p| tilts.push((direction, p.clone())));
+        assert_eq!(cycled, platform.clone().tilt_cycle());
+        assert_eq!(tilts[0], (Direction::North, platform.tilt_north()));
+        assert_eq!(tilts[3], (Direction::East, cycled));
     }
 }
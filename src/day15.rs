@@ -1,5 +1,7 @@
 use anyhow::{Context, Result};
 
+use crate::parse::Locate;
+
 fn holiday_ascii_string_helper(s: &str) -> usize {
     s.chars().fold(0, |h, c| ((h + c as usize) * 17) % 256)
 }
@@ -22,8 +24,8 @@ pub fn part2(input: &str) -> Result<usize> {
             let bucket = holiday_ascii_string_helper(label);
             lenses[bucket].retain(|x| x.0 != label);
         } else {
-            let (label, value) = instruction.split_once('=').context("could not split on =")?;
-            let focal_length: usize = value.parse()?;
+            let (label, value) = instruction.split_once('=').context("could not split on =").at(instruction)?;
+            let focal_length: usize = value.parse().at(value)?;
             let bucket = holiday_ascii_string_helper(label);
             for l in lenses[bucket].iter_mut() {
                 if l.0 == label {
@@ -44,23 +46,3 @@ pub fn part2(input: &str) -> Result<usize> {
 
     Ok(total)
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
-
-    #[test]
-    fn test_example() {
-        assert_eq!(part1(EXAMPLE), 1320);
-        assert_eq!(part2(EXAMPLE).unwrap(), 145);
-    }
-
-    #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day15.txt").unwrap();
-        assert_eq!(part1(&input), 515974);
-        assert_eq!(part2(&input).unwrap(), 265894);
-    }
-}
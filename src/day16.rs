@@ -1,6 +1,13 @@
 use std::{collections::HashSet, str::FromStr};
 
-use anyhow::{bail, Error, Result};
+use anyhow::{bail, Context, Error, Result};
+
+use crate::{
+    grid::{Grid, Posn},
+    point::Direction,
+    render::{self, Image, Rgb},
+    visualize::Frame,
+};
 
 enum Tile {
     Empty,
@@ -24,93 +31,47 @@ impl TryFrom<char> for Tile {
     }
 }
 
-#[derive(Default, Eq, PartialEq, Hash, Copy, Clone)]
-enum Direction {
-    North,
-    South,
-    #[default]
-    East,
-    West,
-}
-
-type Posn = (usize, usize);
-
-#[derive(Default, PartialEq, Eq, Hash, Copy, Clone)]
+#[derive(PartialEq, Eq, Hash, Copy, Clone)]
 struct Beam {
     position: Posn,
     direction: Direction,
 }
 
-struct Contraption(Vec<Vec<Tile>>);
+impl Beam {
+    /// Part 1's beam, which enters at the top left heading east
+    const START: Self = Self { position: (0, 0), direction: Direction::East };
+}
+
+pub struct Contraption(Grid<Tile>);
 
 impl FromStr for Contraption {
     type Err = Error;
     fn from_str(input: &str) -> Result<Self> {
-        let mut contraption: Vec<Vec<Tile>> = Vec::new();
-        for l in input.lines() {
-            let mut row: Vec<Tile> = Vec::new();
-            for c in l.chars() {
-                row.push(c.try_into()?);
-            }
-            contraption.push(row);
-        }
-        Ok(Self(contraption))
-    }
-}
-
-fn try_move(posn: &Posn, direction: &Direction, contraption_dimensions: &Posn) -> Option<Posn> {
-    match direction {
-        Direction::North => {
-            if posn.0 == 0 {
-                None
-            } else {
-                Some((posn.0 - 1, posn.1))
-            }
-        }
-        Direction::South => {
-            if posn.0 < contraption_dimensions.0 - 1 {
-                Some((posn.0 + 1, posn.1))
-            } else {
-                None
-            }
-        }
-        Direction::East => {
-            if posn.1 < contraption_dimensions.1 - 1 {
-                Some((posn.0, posn.1 + 1))
-            } else {
-                None
-            }
-        }
-        Direction::West => {
-            if posn.1 == 0 {
-                None
-            } else {
-                Some((posn.0, posn.1 - 1))
-            }
-        }
+        Ok(Self(input.parse()?))
     }
 }
 
 impl Contraption {
-    fn dimensions(&self) -> Posn {
-        (self.0.len(), self.0[0].len())
+    fn try_move(&self, beam: &Beam) -> Option<Beam> {
+        self.0.step(beam.position, beam.direction).map(|position| Beam { direction: beam.direction, position })
     }
 
-    fn try_move(&self, beam: &Beam) -> Option<Beam> {
-        try_move(&beam.position, &beam.direction, &self.dimensions())
-            .map(|position| Beam { direction: beam.direction, position })
+    fn energized_tiles(&self, start: Beam) -> Grid<bool> {
+        self.energized_tiles_with(start, |_, _| ())
     }
 
-    fn energized_tiles(&self, start: Beam) -> Vec<Vec<bool>> {
-        let mut energized_tiles: Vec<Vec<bool>> = self.0.iter().map(|r| r.iter().map(|_| false).collect()).collect();
+    /// The same as `energized_tiles`, but calls `on_step` with the tiles energized so far and the beams that
+    /// have just moved, once per step of the beams
+    fn energized_tiles_with(&self, start: Beam, mut on_step: impl FnMut(&Grid<bool>, &[Beam])) -> Grid<bool> {
+        let mut energized_tiles = Grid::new(self.0.width(), self.0.height(), false);
         let mut beams: Vec<Beam> = vec![start];
         let mut beam_cache: HashSet<Beam> = HashSet::default();
         beam_cache.insert(start);
         while !beams.is_empty() {
             let mut new_beams: Vec<Beam> = Vec::new();
-            for beam in beams {
-                energized_tiles[beam.position.0][beam.position.1] = true;
-                match self.0[beam.position.0][beam.position.1] {
+            for &beam in &beams {
+                energized_tiles[beam.position] = true;
+                match self.0[beam.position] {
                     Tile::Empty => {
                         if let Some(new_beam) = self.try_move(&beam) {
                             new_beams.push(new_beam);
@@ -146,7 +107,7 @@ impl Contraption {
                                 }
                             }
                             Direction::East | Direction::West => {
-                                for direction in [Direction::North, Direction::South] {
+                                for direction in [beam.direction.turn_left(), beam.direction.turn_right()] {
                                     if let Some(new_beam) = self.try_move(&Beam { position: beam.position, direction })
                                     {
                                         new_beams.push(new_beam);
@@ -163,7 +124,7 @@ impl Contraption {
                                 }
                             }
                             Direction::North | Direction::South => {
-                                for direction in [Direction::East, Direction::West] {
+                                for direction in [beam.direction.turn_left(), beam.direction.turn_right()] {
                                     if let Some(new_beam) = self.try_move(&Beam { position: beam.position, direction })
                                     {
                                         new_beams.push(new_beam);
@@ -174,6 +135,7 @@ impl Contraption {
                     }
                 }
             }
+            on_step(&energized_tiles, &beams);
             beams = Vec::new();
             for beam in new_beams {
                 if beam_cache.insert(beam) {
@@ -186,66 +148,88 @@ impl Contraption {
     }
 }
 
+fn energized_count(contraption: &Contraption, start: Beam) -> usize {
+    contraption.energized_tiles(start).iter().filter(|(_, &x)| x).count()
+}
+
+/// Every beam entering the contraption from one of its edges
+fn edge_beams(contraption: &Contraption) -> Vec<Beam> {
+    let (height, width) = (contraption.0.height(), contraption.0.width());
+    let mut beams: Vec<Beam> = Vec::new();
+    for i in 0..height {
+        beams.push(Beam { direction: Direction::East, position: (i, 0) });
+        beams.push(Beam { direction: Direction::West, position: (i, width - 1) });
+    }
+    for j in 0..width {
+        beams.push(Beam { direction: Direction::South, position: (0, j) });
+        beams.push(Beam { direction: Direction::North, position: (height - 1, j) });
+    }
+    beams
+}
+
 pub fn part1(input: &str) -> Result<usize> {
     let contraption: Contraption = input.parse()?;
-    Ok(contraption.energized_tiles(Beam::default()).iter().map(|r| r.iter().filter(|&&x| x).count()).sum())
+    Ok(energized_count(&contraption, Beam::START))
 }
 
 pub fn part2(input: &str) -> Result<usize> {
     let contraption: Contraption = input.parse()?;
-    let mut current_max_energized = 0;
-    for i in 0..contraption.0.len() {
-        let start = Beam { direction: Direction::East, position: (i, 0) };
-        let energized: usize =
-            contraption.energized_tiles(start).iter().map(|r| r.iter().filter(|&&x| x).count()).sum();
-        current_max_energized = energized.max(current_max_energized);
-
-        let start = Beam { direction: Direction::West, position: (i, contraption.0[0].len() - 1) };
-        let energized: usize =
-            contraption.energized_tiles(start).iter().map(|r| r.iter().filter(|&&x| x).count()).sum();
-        current_max_energized = energized.max(current_max_energized);
-    }
-
-    for j in 0..contraption.0[0].len() {
-        let start = Beam { direction: Direction::South, position: (0, j) };
-        let energized: usize =
-            contraption.energized_tiles(start).iter().map(|r| r.iter().filter(|&&x| x).count()).sum();
-        current_max_energized = energized.max(current_max_energized);
+    Ok(edge_beams(&contraption).into_iter().map(|start| energized_count(&contraption, start)).max().unwrap_or(0))
+}
 
-        let start = Beam { direction: Direction::North, position: (contraption.0.len() - 1, j) };
-        let energized: usize =
-            contraption.energized_tiles(start).iter().map(|r| r.iter().filter(|&&x| x).count()).sum();
-        current_max_energized = energized.max(current_max_energized);
+/// Part 1's beam, or the beam that energizes the most tiles for part 2
+fn start_beam(contraption: &Contraption, part: u8) -> Result<Beam> {
+    match part {
+        1 => Ok(Beam::START),
+        _ => edge_beams(contraption)
+            .into_iter()
+            .max_by_key(|&start| energized_count(contraption, start))
+            .context("The contraption is empty"),
     }
-    Ok(current_max_energized)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = r".|...\....
-|.-.\.....
-.....|-...
-........|.
-..........
-.........\
-..../.\\..
-.-.-/..|..
-.|....-|.\
-..//.|....
-";
-
-    #[test]
-    fn test_example() {
-        assert_eq!(part1(EXAMPLE).unwrap(), 46);
-        assert_eq!(part2(EXAMPLE).unwrap(), 51);
-    }
+/// The empty tiles energized by part 1's beam (or the best beam for part 2) in yellow, with mirrors and splitters in
+/// grey
+pub fn render(input: &str, part: u8) -> Result<Image> {
+    let contraption: Contraption = input.parse()?;
+    let start = start_beam(&contraption, part)?;
+    let energized = contraption.energized_tiles(start);
+    let mut image = contraption.0.map(|tile| if matches!(tile, Tile::Empty) { Rgb::BLACK } else { Rgb::GREY });
+    let energized_empty_tiles =
+        energized.iter().filter(|&(posn, &e)| e && matches!(contraption.0[posn], Tile::Empty)).map(|(posn, _)| posn);
+    render::overlay(&mut image, energized_empty_tiles, Rgb::YELLOW);
+    Ok(image)
+}
 
-    #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day16.txt").unwrap();
-        assert_eq!(part1(&input).unwrap(), 7415);
-        assert_eq!(part2(&input).unwrap(), 7943);
-    }
+/// The beams spreading out through the contraption, drawn as arrows on top of the energized tiles
+pub fn visualize(input: &str, part: u8, on_frame: &mut dyn FnMut(Frame)) -> Result<()> {
+    let contraption: Contraption = input.parse()?;
+    let start = start_beam(&contraption, part)?;
+    let tiles = contraption.0.map(|tile| match tile {
+        Tile::Empty => '.',
+        Tile::MirrorFwd => '/',
+        Tile::MirrorBack => '\\',
+        Tile::SplitterVert => '|',
+        Tile::SplitterHori => '-',
+    });
+    let mut steps = 0;
+    contraption.energized_tiles_with(start, |energized, beams| {
+        steps += 1;
+        let mut cells = tiles.map(|&c| (c, Rgb::GREY));
+        for (posn, _) in energized.iter().filter(|&(_, &e)| e) {
+            cells[posn].1 = Rgb::YELLOW;
+        }
+        for beam in beams {
+            let arrow = match beam.direction {
+                Direction::North => '^',
+                Direction::East => '>',
+                Direction::South => 'v',
+                Direction::West => '<',
+            };
+            cells[beam.position] = (if tiles[beam.position] == '.' { arrow } else { tiles[beam.position] }, Rgb::RED);
+        }
+        let count = energized.iter().filter(|(_, &e)| e).count();
+        on_frame(Frame { cells, caption: format!("Step {steps}, {} beams, {count} tiles energized", beams.len()) });
+    });
+    Ok(())
 }
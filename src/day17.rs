@@ -2,80 +2,44 @@ use anyhow::{Context, Error, Result};
 use pathfinding::prelude::dijkstra;
 use std::str::FromStr;
 
-struct City(Vec<Vec<usize>>);
+use crate::{
+    grid::Grid,
+    point::{Direction, Point},
+    render::{self, Image, Rgb},
+};
+
+pub struct City(Grid<usize>);
 
 impl FromStr for City {
     type Err = Error;
     fn from_str(s: &str) -> Result<Self> {
-        let mut map: Vec<Vec<usize>> = Vec::new();
-        for l in s.lines() {
-            let mut row: Vec<usize> = Vec::new();
-            for c in l.chars() {
-                row.push(c.to_digit(10).context("Not a number")? as usize);
-            }
-            map.push(row);
-        }
-        Ok(Self(map))
-    }
-}
-
-type Posn = (isize, isize);
-
-#[derive(Default, Eq, PartialEq, Hash, Clone, Copy)]
-enum Direction {
-    North,
-    South,
-    #[default]
-    East,
-    West,
-}
-
-impl From<Direction> for Posn {
-    fn from(value: Direction) -> Self {
-        match value {
-            Direction::North => (-1, 0),
-            Direction::South => (1, 0),
-            Direction::East => (0, 1),
-            Direction::West => (0, -1),
-        }
+        Ok(Self(Grid::parse_with(s, |c| c.to_digit(10).map(|d| d as usize).context("Not a number"))?))
     }
 }
 
-fn posn_add((a, b): Posn, (c, d): Posn) -> Posn {
-    (a + c, b + d)
-}
-
-#[derive(Default, Eq, PartialEq, Hash, Clone, Copy)]
+#[derive(Eq, PartialEq, Hash, Clone, Copy)]
 struct Crucible {
     direction: Direction,
     moves_in_direction: u8,
-    position: Posn,
+    position: Point,
 }
 
 impl Crucible {
-    fn cost(&self, map: &City) -> usize {
-        map.0[self.position.0 as usize][self.position.1 as usize]
-    }
+    /// The crucible at the top left before its first move, heading east
+    const START: Self = Self { direction: Direction::East, moves_in_direction: 0, position: Point::ORIGIN };
 
-    fn is_valid(&self, map: &City) -> bool {
-        self.position.0 >= 0
-            && self.position.1 >= 0
-            && self.position.0 < map.0.len() as isize
-            && self.position.1 < map.0[0].len() as isize
+    /// The heat lost by entering this crucible's position, or None if it has left the city
+    fn cost(&self, map: &City) -> Option<usize> {
+        self.position.to_posn().and_then(|posn| map.0.get(posn)).copied()
     }
 
     fn neighbours(&self, map: &City) -> Vec<(Crucible, usize)> {
         let mut neighbours: Vec<(Crucible, usize)> = Vec::new();
 
-        let next_directions: [Direction; 2] = match self.direction {
-            Direction::East | Direction::West => [Direction::North, Direction::South],
-            Direction::North | Direction::South => [Direction::East, Direction::West],
-        };
-        for direction in next_directions {
-            let neighbour =
-                Crucible { direction, moves_in_direction: 1, position: posn_add(self.position, direction.into()) };
-            if neighbour.is_valid(map) {
-                neighbours.push((neighbour, neighbour.cost(map)));
+        for direction in [self.direction.turn_left(), self.direction.turn_right()] {
+            let neighbour = Crucible { direction, moves_in_direction: 1, position: self.position + direction };
+            if let Some(cost) = neighbour.cost(map) {
+                neighbours.push((neighbour, cost));
             }
         }
 
@@ -84,10 +48,10 @@ impl Crucible {
             let neighbour = Crucible {
                 direction: self.direction,
                 moves_in_direction: self.moves_in_direction + 1,
-                position: posn_add(self.position, self.direction.into()),
+                position: self.position + self.direction,
             };
-            if neighbour.is_valid(map) {
-                neighbours.push((neighbour, neighbour.cost(map)));
+            if let Some(cost) = neighbour.cost(map) {
+                neighbours.push((neighbour, cost));
             }
         }
 
@@ -101,23 +65,18 @@ impl Crucible {
             let neighbour = Crucible {
                 direction: self.direction,
                 moves_in_direction: self.moves_in_direction + 1,
-                position: posn_add(self.position, self.direction.into()),
+                position: self.position + self.direction,
             };
-            if neighbour.is_valid(map) {
-                neighbours.push((neighbour, neighbour.cost(map)));
+            if let Some(cost) = neighbour.cost(map) {
+                neighbours.push((neighbour, cost));
             }
         }
 
         if self.moves_in_direction >= 4 || self.moves_in_direction == 0 {
-            let next_directions: [Direction; 2] = match self.direction {
-                Direction::East | Direction::West => [Direction::North, Direction::South],
-                Direction::North | Direction::South => [Direction::East, Direction::West],
-            };
-            for direction in next_directions {
-                let neighbour =
-                    Crucible { direction, moves_in_direction: 1, position: posn_add(self.position, direction.into()) };
-                if neighbour.is_valid(map) {
-                    neighbours.push((neighbour, neighbour.cost(map)));
+            for direction in [self.direction.turn_left(), self.direction.turn_right()] {
+                let neighbour = Crucible { direction, moves_in_direction: 1, position: self.position + direction };
+                if let Some(cost) = neighbour.cost(map) {
+                    neighbours.push((neighbour, cost));
                 }
             }
         }
@@ -126,63 +85,30 @@ impl Crucible {
     }
 }
 
+/// The cheapest path from the top left to the bottom right, for an ordinary crucible or (in part 2) an ultra crucible
+fn cheapest_path(map: &City, ultra: bool) -> Result<(Vec<Crucible>, usize)> {
+    let end = Point::new(map.0.height() as isize - 1, map.0.width() as isize - 1);
+    if ultra {
+        dijkstra(&Crucible::START, |c| c.ultra_neighbours(map), |c| c.position == end && c.moves_in_direction >= 4)
+    } else {
+        dijkstra(&Crucible::START, |c| c.neighbours(map), |c| c.position == end)
+    }
+    .context("Could not find a path through the city using dijkstra's algorithm.")
+}
+
 pub fn part1(input: &str) -> Result<usize> {
-    let map: City = input.parse()?;
-    let end: Posn = (map.0.len() as isize - 1, map.0[0].len() as isize - 1);
-    let (_path, cost) = dijkstra(&Crucible::default(), |c| c.neighbours(&map), |c| c.position == end)
-        .context("Could not find a path through the city using dijkstra's algorithm.")?;
-    Ok(cost)
+    Ok(cheapest_path(&input.parse()?, false)?.1)
 }
 
 pub fn part2(input: &str) -> Result<usize> {
-    let map: City = input.parse()?;
-    let end: Posn = (map.0.len() as isize - 1, map.0[0].len() as isize - 1);
-    let (_path, cost) = dijkstra(
-        &Crucible::default(),
-        |c| c.ultra_neighbours(&map),
-        |c| c.position == end && c.moves_in_direction >= 4,
-    )
-    .context("Could not find a path through the city using dijkstra's algorithm.")?;
-    Ok(cost)
+    Ok(cheapest_path(&input.parse()?, true)?.1)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "2413432311323
-3215453535623
-3255245654254
-3446585845452
-4546657867536
-1438598798454
-4457876987766
-3637877979653
-4654967986887
-4564679986453
-1224686865563
-2546548887735
-4322674655533
-";
-
-    const EXAMPLE2: &str = "111111111111
-999999999991
-999999999991
-999999999991
-999999999991
-";
-
-    #[test]
-    fn test_example() {
-        assert_eq!(part1(EXAMPLE).unwrap(), 102);
-        assert_eq!(part2(EXAMPLE).unwrap(), 94);
-        assert_eq!(part2(EXAMPLE2).unwrap(), 71);
-    }
-
-    #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day17.txt").unwrap();
-        assert_eq!(part1(&input).unwrap(), 870);
-        assert_eq!(part2(&input).unwrap(), 1063);
-    }
+/// The cheapest path in red, over the city blocks shaded from light (little heat loss) to dark
+pub fn render(input: &str, part: u8) -> Result<Image> {
+    let map: City = input.parse()?;
+    let (path, _cost) = cheapest_path(&map, part == 2)?;
+    let mut image = map.0.map(|&heat_loss| Rgb::grey(255 - (heat_loss.min(9) * 25) as u8));
+    render::overlay(&mut image, path.iter().filter_map(|c| c.position.to_posn()), Rgb::RED);
+    Ok(image)
 }
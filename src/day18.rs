@@ -1,36 +1,22 @@
-use std::str::FromStr;
+use std::{collections::HashSet, str::FromStr};
 
-use anyhow::{anyhow, Context, Error, Result};
+use anyhow::{anyhow, bail, Context, Error, Result};
+use pathfinding::directed::bfs::bfs_reach;
 
-#[derive(Copy, Clone)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+use crate::{
+    grid::{Grid, Posn},
+    parse::Locate,
+    point::{self, Direction, Point},
+    render::{self, Image, Rgb},
+};
 
-impl FromStr for Direction {
-    type Err = Error;
-    fn from_str(s: &str) -> Result<Self> {
-        match s {
-            "U" => Ok(Self::Up),
-            "D" => Ok(Self::Down),
-            "R" => Ok(Self::Right),
-            "L" => Ok(Self::Left),
-            _ => Err(anyhow!("Could not parse direction from {s}")),
-        }
-    }
-}
-
-impl From<Direction> for Posn {
-    fn from(value: Direction) -> Self {
-        match value {
-            Direction::Down => (1, 0),
-            Direction::Up => (-1, 0),
-            Direction::Right => (0, 1),
-            Direction::Left => (0, -1),
-        }
+fn parse_direction(s: &str) -> Result<Direction> {
+    match s {
+        "U" => Ok(Direction::North),
+        "D" => Ok(Direction::South),
+        "R" => Ok(Direction::East),
+        "L" => Ok(Direction::West),
+        _ => Err(anyhow!("Could not parse direction from {s}")).at(s),
     }
 }
 
@@ -40,10 +26,28 @@ struct Color(u32);
 impl FromStr for Color {
     type Err = Error;
     fn from_str(input: &str) -> Result<Self> {
-        Ok(Self(u32::from_str_radix(
-            input.strip_prefix("(#").context("missing prefix")?.strip_suffix(')').context("missing suffix")?,
-            16,
-        )?))
+        let hex = input
+            .strip_prefix("(#")
+            .context("missing prefix (#")
+            .at(input)?
+            .strip_suffix(')')
+            .context("missing suffix )")
+            .at(input)?;
+        Ok(Self(u32::from_str_radix(hex, 16).at(hex)?))
+    }
+}
+
+impl Color {
+    /// The real instruction hidden in the colour: five hex digits of distance, then one digit of direction
+    fn decode(self) -> Result<(Direction, usize)> {
+        let direction = match self.0 & 0xf {
+            0 => Direction::East,
+            1 => Direction::South,
+            2 => Direction::West,
+            3 => Direction::North,
+            d => bail!("{d:x} is not a direction digit in colour #{:06x}", self.0),
+        };
+        Ok((direction, (self.0 >> 4) as usize))
     }
 }
 
@@ -57,27 +61,25 @@ impl FromStr for DigInstruction {
     type Err = Error;
     fn from_str(input: &str) -> Result<Self> {
         if let [dir, amt, col] = input.split_ascii_whitespace().collect::<Vec<&str>>()[..] {
-            Ok(Self { direction: dir.parse()?, amount: amt.parse()?, color: col.parse()? })
+            Ok(Self { direction: parse_direction(dir)?, amount: amt.parse().at(amt)?, color: col.parse()? })
         } else {
-            Err(anyhow!("Could not split the dig instruction into three parts"))
+            Err(anyhow!("Could not split the dig instruction into three parts")).at(input)
         }
     }
 }
 
-type Posn = (isize, isize);
 impl DigInstruction {
-    fn dig(&self, mut start: Posn) -> Vec<(Posn, Color)> {
-        let direction: Posn = self.direction.into();
-        let mut dugout: Vec<(Posn, Color)> = Vec::new();
+    fn dig(&self, mut start: Point) -> Vec<(Point, Color)> {
+        let mut dugout: Vec<(Point, Color)> = Vec::new();
         for _ in 0..self.amount {
-            start = (start.0 + direction.0, start.1 + direction.1);
+            start = start + self.direction;
             dugout.push((start, self.color));
         }
         dugout
     }
 }
 
-struct DigPlan(Vec<DigInstruction>);
+pub struct DigPlan(Vec<DigInstruction>);
 
 impl FromStr for DigPlan {
     type Err = Error;
@@ -91,10 +93,10 @@ impl FromStr for DigPlan {
 }
 
 impl DigPlan {
-    fn dig(&self) -> Vec<(Posn, Color)> {
-        let mut dugout: Vec<(Posn, Color)> = Vec::new();
+    fn dig(&self) -> Vec<(Point, Color)> {
+        let mut dugout: Vec<(Point, Color)> = Vec::new();
         for instruction in self.0.iter() {
-            let start = if let Some((start, _)) = dugout.last() { *start } else { (0, 0) };
+            let start = if let Some((start, _)) = dugout.last() { *start } else { Point::ORIGIN };
             dugout.extend(instruction.dig(start));
         }
         dugout
@@ -105,114 +107,93 @@ impl DigPlan {
         if dugout.is_empty() {
             return Vec::new(); // Not relevant for AOC, but lets be good coders
         }
-        let mut min: Posn = dugout[0].0;
-        let mut max: Posn = dugout[0].0;
+        let mut min: Point = dugout[0].0;
+        let mut max: Point = dugout[0].0;
         for (dug, _) in dugout.iter().skip(1) {
-            min = (dug.0.min(min.0), dug.1.min(min.1));
-            max = (dug.0.max(max.0), dug.1.max(max.1));
+            min = Point::new(dug.row.min(min.row), dug.column.min(min.column));
+            max = Point::new(dug.row.max(max.row), dug.column.max(max.column));
         }
 
-        let height = (max.0 + 1) - min.0;
-        let width = (max.1 + 1) - min.1;
+        let height = (max.row + 1) - min.row;
+        let width = (max.column + 1) - min.column;
 
         let mut map: Vec<Vec<bool>> = (0..height).map(|_| (0..width).map(|_| false).collect()).collect();
 
         for (dug, _) in dugout.iter() {
-            let dug_idx = (dug.0 - min.0, dug.1 - min.1);
-            map[dug_idx.0 as usize][dug_idx.1 as usize] = true;
+            let (i, j) = (*dug - min).to_posn().expect("min is the smallest row and column dug");
+            map[i][j] = true;
         }
 
         map
     }
 
+    /// The trench with its inside dug out too, found by flooding in from a border of undug ground around the map
     fn dig_map_full(&self) -> Vec<Vec<bool>> {
-        #[derive(PartialEq, Eq)]
-        enum DigState {
-            Inside,
-            Outside,
-            InnerEdge,
-            OuterEdge,
-        }
+        let dig_map = self.dig_map();
+        let width = dig_map.first().map_or(0, Vec::len) + 2;
+        let mut padded = vec![vec![false; width]];
+        padded.extend(dig_map.iter().map(|row| [&[false], &row[..], &[false]].concat()));
+        padded.push(vec![false; width]);
+        let map = Grid::from_rows(padded).expect("every row is the same width");
 
-        let mut dig_map = self.dig_map();
-
-        // The edge cases are a bit tricky: store the top and bottom to help with this
-        let mut top: Vec<usize> = Vec::new();
-        let mut bottom: Vec<usize> = Vec::new();
-        for (i, row) in dig_map.iter().enumerate() {
-            for (j, &is_wall) in row.iter().enumerate() {
-                if is_wall {
-                    if j >= top.len() {
-                        top.push(i);
-                        bottom.push(i);
-                    } else {
-                        bottom[j] = i;
-                    }
-                }
-            }
-        }
-        for (i, row) in dig_map.iter_mut().enumerate() {
-            let mut state = DigState::Outside;
-            for (j, item) in row.iter_mut().enumerate() {
-                match state {
-                    DigState::Outside => {
-                        if *item {
-                            state = DigState::InnerEdge;
-                        }
-                    }
-                    DigState::Inside => {
-                        if *item {
-                            state = DigState::OuterEdge;
-                        } else {
-                            if top[j] < i && bottom[j] > i {
-                                *item = true;
-                            }
-                        }
-                    }
-                    DigState::InnerEdge => {
-                        if !*item {
-                            state = DigState::Inside;
-                            if top[j] < i && bottom[j] > i {
-                                *item = true;
-                            }
-                        }
-                    }
-                    DigState::OuterEdge => {
-                        if !*item {
-                            state = DigState::Outside;
-                        }
-                    }
-                }
-            }
-        }
+        let outside: HashSet<Posn> =
+            bfs_reach((0, 0), |&posn| map.neighbours4(posn).filter(|&next| !map[next]).collect::<Vec<_>>()).collect();
+        (1..map.height() - 1).map(|i| (1..map.width() - 1).map(|j| !outside.contains(&(i, j))).collect()).collect()
+    }
+}
 
-        dig_map
+/// The number of cubic metres dug out by following the steps from the origin: the trench itself plus the grid
+/// points inside it. The steps must lead back to the origin.
+fn lagoon_volume(steps: impl IntoIterator<Item = (Direction, usize)>) -> Result<usize> {
+    let mut corners = vec![Point::ORIGIN];
+    let mut trench: usize = 0;
+    for (direction, amount) in steps {
+        let last = *corners.last().expect("starts at the origin");
+        let step = Point::from(direction);
+        let moved = |start: isize, delta: isize| start.checked_add(delta.checked_mul(isize::try_from(amount).ok()?)?);
+        let next = moved(last.row, step.row).zip(moved(last.column, step.column)).with_context(|| {
+            format!("Digging {amount} metres from row {}, column {} goes too far", last.row, last.column)
+        })?;
+        corners.push(Point::new(next.0, next.1));
+        trench = trench.checked_add(amount).context("The trench is too long")?;
     }
+    let end = corners.pop().expect("starts at the origin");
+    if end != Point::ORIGIN {
+        bail!("The dig plan ends at row {}, column {} instead of back where it started", end.row, end.column);
+    }
+    let inside = point::interior_points(&corners).context("The trench crosses or doubles back on itself")?;
+    Ok(inside + trench)
 }
 
 pub fn part1(input: &str) -> Result<usize> {
-    Ok(input.parse::<DigPlan>()?.dig_map_full().iter().map(|r| r.iter().filter(|&&v| v).count()).sum())
+    let plan: DigPlan = input.parse()?;
+    lagoon_volume(plan.0.iter().map(|instruction| (instruction.direction, instruction.amount)))
+}
+
+pub fn part2(input: &str) -> Result<usize> {
+    let plan: DigPlan = input.parse()?;
+    lagoon_volume(plan.0.iter().map(|instruction| instruction.color.decode()).collect::<Result<Vec<_>>>()?)
+}
+
+/// The lagoon in grey, outlined by each trench in the colour from the dig plan
+pub fn render(input: &str) -> Result<Image> {
+    let plan: DigPlan = input.parse()?;
+    let mut image = Grid::from_rows(plan.dig_map_full())?.map(|&dug| if dug { Rgb::GREY } else { Rgb::BLACK });
+    let dugout = plan.dig();
+    let top_left = Point::new(
+        dugout.iter().map(|(dug, _)| dug.row).min().unwrap_or(0),
+        dugout.iter().map(|(dug, _)| dug.column).min().unwrap_or(0),
+    );
+    for (dug, color) in dugout {
+        render::overlay(&mut image, (dug - top_left).to_posn(), Rgb::from(color.0));
+    }
+    Ok(image)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    const EXAMPLE: &str = "R 6 (#70c710)
-D 5 (#0dc571)
-L 2 (#5713f0)
-D 2 (#d2c081)
-R 2 (#59c680)
-D 2 (#411b91)
-L 5 (#8ceee2)
-U 2 (#caa173)
-L 1 (#1b58a2)
-U 2 (#caa171)
-R 2 (#7807d2)
-U 3 (#a77fa3)
-L 2 (#015232)
-U 2 (#7a21e3)
-";
+    use crate::examples;
 
     const EXPECTED_DIG_MAP: &str = "#######
 #.....#
@@ -240,7 +221,7 @@ U 2 (#7a21e3)
 
     #[test]
     fn test_dig_map() {
-        let plan: DigPlan = EXAMPLE.parse().unwrap();
+        let plan: DigPlan = examples::input(18, "example").parse().unwrap();
         let expected: Vec<Vec<bool>> =
             EXPECTED_DIG_MAP.lines().map(|l| l.chars().map(|c| c == '#').collect()).collect();
         assert_eq!(plan.dig_map(), expected);
@@ -248,19 +229,34 @@ U 2 (#7a21e3)
 
     #[test]
     fn test_dig_map_full() {
-        let plan: DigPlan = EXAMPLE.parse().unwrap();
+        let plan: DigPlan = examples::input(18, "example").parse().unwrap();
         let expected: Vec<Vec<bool>> =
             EXPECTED_FILLED_DIG_MAP.lines().map(|l| l.chars().map(|c| c == '#').collect()).collect();
         assert_eq!(plan.dig_map_full(), expected);
     }
 
     #[test]
-    fn test_example() {
-        assert_eq!(part1(EXAMPLE).unwrap(), 62);
+    fn test_decode() {
+        assert_eq!(Color(0x70c710).decode().unwrap(), (Direction::East, 461937));
+        assert_eq!(Color(0x7a21e3).decode().unwrap(), (Direction::North, 500254));
+        assert!(Color(0x000014).decode().is_err());
+    }
+
+    #[test]
+    fn test_lagoon_volume_agrees_with_dig_map_full() {
+        crate::generate::check_cases(50, |rng| {
+            let input = crate::generate::input(18, rng.next_u64(), 8).unwrap();
+            let plan: DigPlan = input.parse().unwrap();
+            let filled: usize = plan.dig_map_full().iter().map(|r| r.iter().filter(|&&v| v).count()).sum();
+            assert_eq!(part1(&input).unwrap(), filled, "{input}");
+        });
     }
 
     #[test]
-    fn test_solution() {
-        assert_eq!(part1(EXAMPLE).unwrap(), 62);
+    fn test_lagoon_volume_needs_a_loop() {
+        let unclosed = part1("R 5 (#000050)\nD 5 (#000051)\n").unwrap_err();
+        assert_eq!(unclosed.to_string(), "The dig plan ends at row 5, column 5 instead of back where it started");
+        assert!(part1("R 5 (#000050)\nL 5 (#000052)\n").is_err());
+        assert!(part1("R 18446744073709551615 (#000050)\nL 18446744073709551615 (#000052)\n").is_err());
     }
 }
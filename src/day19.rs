@@ -1,14 +1,15 @@
-use anyhow::{anyhow, bail, Context, Error, Result};
+use anyhow::{anyhow, Context, Error, Result};
 use std::{
     borrow::Borrow,
     collections::HashSet,
     hash::{Hash, Hasher},
-    ops::Range,
     str::FromStr,
 };
 
+use crate::{interval::IntervalSet, parse::Locate};
+
 #[derive(Debug)]
-struct Part {
+pub struct Part {
     x: usize,
     m: usize,
     a: usize,
@@ -24,19 +25,21 @@ impl From<Part> for usize {
 impl FromStr for Part {
     type Err = Error;
     fn from_str(input: &str) -> Result<Self> {
-        let trimmed_input = input.strip_prefix('{').context("missing {")?.strip_suffix('}').context("missing }")?;
+        let trimmed_input =
+            input.strip_prefix('{').context("missing {").at(input)?.strip_suffix('}').context("missing }").at(input)?;
         let [x, m, a, s] = trimmed_input.split(',').collect::<Vec<&str>>()[..] else {
-            bail!("Could not parse part into 4 components: {input}");
+            return Err(anyhow!("Could not parse part into 4 components")).at(trimmed_input);
         };
-        Ok(Self {
-            x: x.strip_prefix("x=").context("Missing x=")?.parse()?,
-            m: m.strip_prefix("m=").context("Missing m=")?.parse()?,
-            a: a.strip_prefix("a=").context("Missing a=")?.parse()?,
-            s: s.strip_prefix("s=").context("Missing s=")?.parse()?,
-        })
+        Ok(Self { x: rating(x, "x=")?, m: rating(m, "m=")?, a: rating(a, "a=")?, s: rating(s, "s=")? })
     }
 }
 
+/// One of a part's ratings, such as the 787 in x=787
+fn rating(input: &str, prefix: &str) -> Result<usize> {
+    let value = input.strip_prefix(prefix).with_context(|| format!("Missing {prefix}")).at(input)?;
+    value.parse().at(value)
+}
+
 enum Operator {
     LessThan,
     GreaterThan,
@@ -58,19 +61,19 @@ impl FromStr for Test {
     type Err = Error;
     fn from_str(input: &str) -> Result<Self> {
         let mut input_chars = input.chars();
-        let field = match input_chars.next().context("expected xmas, got nothing")? {
+        let field = match input_chars.next().context("expected xmas, got nothing").at(input)? {
             'x' => PartField::X,
             'm' => PartField::M,
             'a' => PartField::A,
             's' => PartField::S,
-            c => bail!("Expected xmas, got {c}: {input}"),
+            c => return Err(anyhow!("Expected xmas, got {c}")).at(&input[..c.len_utf8()]),
         };
-        let operator = match input_chars.next().context("Expected <>, got nothing")? {
+        let operator = match input_chars.next().context("Expected <>, got nothing").at(input)? {
             '<' => Operator::LessThan,
             '>' => Operator::GreaterThan,
-            c => bail!("Expected <>, got {c}: {input}"),
+            c => return Err(anyhow!("Expected <>, got {c}")).at(&input[1..1 + c.len_utf8()]),
         };
-        let value: usize = input_chars.collect::<String>().parse()?;
+        let value: usize = input_chars.as_str().parse().at(input_chars.as_str())?;
         Ok(Self::Comparison { field, operator, value })
     }
 }
@@ -131,84 +134,26 @@ impl Rule {
         }
     }
 
-    fn apply_range(&self, mut part_range: PartRange) -> Vec<(PartRange, Option<&Conclusion>)> {
-        let mut conclusions: Vec<(PartRange, Option<&Conclusion>)> = Vec::new();
-        match &self.test {
-            Test::Absolute => conclusions.push((part_range, Some(&self.conclusion))),
-            Test::Comparison { field, operator, value } => {
-                let part_values = match field {
-                    PartField::X => &part_range.x,
-                    PartField::M => &part_range.m,
-                    PartField::A => &part_range.a,
-                    PartField::S => &part_range.s,
-                };
-                match operator {
-                    Operator::GreaterThan => {
-                        if part_values.contains(value) {
-                            let mut first_part = part_range.clone();
-                            match field {
-                                PartField::X => {
-                                    first_part.x.end = *value + 1;
-                                    part_range.x.start = *value + 1;
-                                }
-                                PartField::M => {
-                                    first_part.m.end = *value + 1;
-                                    part_range.m.start = *value + 1;
-                                }
-                                PartField::A => {
-                                    first_part.a.end = *value + 1;
-                                    part_range.a.start = *value + 1;
-                                }
-                                PartField::S => {
-                                    first_part.s.end = *value + 1;
-                                    part_range.s.start = *value + 1;
-                                }
-                            };
-                            conclusions.push((first_part, None));
-                            conclusions.push((part_range, Some(&self.conclusion)));
-                        } else if part_values.start > *value {
-                            conclusions.push((part_range, Some(&self.conclusion)))
-                        } else {
-                            conclusions.push((part_range, None))
-                        }
-                    }
-                    Operator::LessThan => {
-                        if part_values.contains(value) {
-                            let mut first_part = part_range.clone();
-                            match field {
-                                PartField::X => {
-                                    first_part.x.end = *value;
-                                    part_range.x.start = *value;
-                                }
-                                PartField::M => {
-                                    first_part.m.end = *value;
-                                    part_range.m.start = *value;
-                                }
-                                PartField::A => {
-                                    first_part.a.end = *value;
-                                    part_range.a.start = *value;
-                                }
-                                PartField::S => {
-                                    first_part.s.end = *value;
-                                    part_range.s.start = *value;
-                                }
-                            };
-                            conclusions.push((first_part, Some(&self.conclusion)));
-                            conclusions.push((part_range, None));
-                        } else if part_values.end <= *value {
-                            conclusions.push((part_range, Some(&self.conclusion)))
-                        } else {
-                            conclusions.push((part_range, None))
-                        }
-                    }
-                }
+    fn apply_range(&self, part_range: PartRange) -> Vec<(PartRange, Option<&Conclusion>)> {
+        let Test::Comparison { field, operator, value } = &self.test else {
+            return vec![(part_range, Some(&self.conclusion))];
+        };
+        let values = part_range.field(field);
+        let (matched, unmatched) = match operator {
+            Operator::GreaterThan => {
+                let (below, above) = values.split_at(value + 1);
+                (above, below)
             }
-        }
-        conclusions
+            Operator::LessThan => values.split_at(*value),
+        };
+        [(part_range.with(field, unmatched), None), (part_range.with(field, matched), Some(&self.conclusion))]
+            .into_iter()
+            .filter(|(range, _)| !range.is_empty())
+            .collect()
     }
 }
 
-struct WorkFlow {
+pub struct WorkFlow {
     name: String,
     rules: Vec<Rule>,
 }
@@ -220,8 +165,8 @@ impl PartialEq for WorkFlow {
 }
 impl Eq for WorkFlow {}
 
-impl Borrow<String> for WorkFlow {
-    fn borrow(&self) -> &String {
+impl Borrow<str> for WorkFlow {
+    fn borrow(&self) -> &str {
         &self.name
     }
 }
@@ -229,11 +174,8 @@ impl Borrow<String> for WorkFlow {
 impl FromStr for WorkFlow {
     type Err = Error;
     fn from_str(input: &str) -> Result<Self> {
-        let (name, rs) = input
-            .strip_suffix('}')
-            .with_context(|| format!("Missing }}: {input}"))?
-            .split_once('{')
-            .with_context(|| format!("Missing {{: {input}"))?;
+        let (name, rs) =
+            input.strip_suffix('}').context("Missing }").at(input)?.split_once('{').context("Missing {").at(input)?;
         let mut rules: Vec<Rule> = Vec::new();
         for r in rs.split(',') {
             rules.push(r.parse()?);
@@ -249,13 +191,13 @@ impl Hash for WorkFlow {
 }
 
 impl WorkFlow {
-    fn accepts(&self, part: &Part, workflows: &HashSet<WorkFlow>) -> Result<bool> {
+    pub fn accepts(&self, part: &Part, workflows: &HashSet<WorkFlow>) -> Result<bool> {
         for rule in self.rules.iter() {
             match rule.apply(part) {
                 Some(Conclusion::Accept) => return Ok(true),
                 Some(Conclusion::Reject) => return Ok(false),
                 Some(Conclusion::WorkFlow(name)) => {
-                    let wf = workflows.get(name).with_context(|| format!("Could not find workflow {name}"))?;
+                    let wf = workflows.get(name.as_str()).with_context(|| format!("Could not find workflow {name}"))?;
                     return wf.accepts(part, workflows);
                 }
                 None => {}
@@ -264,7 +206,7 @@ impl WorkFlow {
         Err(anyhow!("no conclusion for workflow {} on part {:?}", self.name, part))
     }
 
-    fn accepts_range(&self, part_range: PartRange, workflows: &HashSet<WorkFlow>) -> Result<Vec<PartRange>> {
+    pub fn accepts_range(&self, part_range: PartRange, workflows: &HashSet<WorkFlow>) -> Result<Vec<PartRange>> {
         let mut accepted: Vec<PartRange> = Vec::new();
         let mut part_ranges = vec![part_range];
         for rule in self.rules.iter() {
@@ -276,7 +218,9 @@ impl WorkFlow {
                         Some(Conclusion::Accept) => accepted.push(range),
                         Some(Conclusion::Reject) => {}
                         Some(Conclusion::WorkFlow(name)) => {
-                            let wf = workflows.get(name).with_context(|| format!("Could not find workflow {name}"))?;
+                            let wf = workflows
+                                .get(name.as_str())
+                                .with_context(|| format!("Could not find workflow {name}"))?;
                             accepted.extend(wf.accepts_range(range, workflows)?);
                         }
                     }
@@ -288,7 +232,7 @@ impl WorkFlow {
     }
 }
 
-pub fn part1(input: &str) -> Result<usize> {
+pub fn parse_input(input: &str) -> Result<(HashSet<WorkFlow>, Vec<Part>)> {
     let mut workflows: HashSet<WorkFlow> = HashSet::default();
 
     let (ws, ps) = input.split_once("\n\n").context("Could not split input into workflows and parts")?;
@@ -302,7 +246,12 @@ pub fn part1(input: &str) -> Result<usize> {
         parts.push(p.parse()?);
     }
 
-    let start: &WorkFlow = workflows.get(&"in".to_string()).context("Could not find starting workflow")?;
+    Ok((workflows, parts))
+}
+
+pub fn part1(input: &str) -> Result<usize> {
+    let (workflows, parts) = parse_input(input)?;
+    let start: &WorkFlow = workflows.get("in").context("Could not find starting workflow")?;
 
     let mut value_of_accepted_parts = 0;
     for part in parts {
@@ -315,16 +264,43 @@ pub fn part1(input: &str) -> Result<usize> {
 }
 
 #[derive(Clone)]
-struct PartRange {
-    x: Range<usize>,
-    m: Range<usize>,
-    a: Range<usize>,
-    s: Range<usize>,
+pub struct PartRange {
+    x: IntervalSet,
+    m: IntervalSet,
+    a: IntervalSet,
+    s: IntervalSet,
 }
 
 impl Default for PartRange {
     fn default() -> Self {
-        Self { x: 1..4001, m: 1..4001, a: 1..4001, s: 1..4001 }
+        Self { x: (1..4001).into(), m: (1..4001).into(), a: (1..4001).into(), s: (1..4001).into() }
+    }
+}
+
+impl PartRange {
+    fn field(&self, field: &PartField) -> &IntervalSet {
+        match field {
+            PartField::X => &self.x,
+            PartField::M => &self.m,
+            PartField::A => &self.a,
+            PartField::S => &self.s,
+        }
+    }
+
+    /// A copy of this range with `field` replaced by `values`
+    fn with(&self, field: &PartField, values: IntervalSet) -> Self {
+        let mut range = self.clone();
+        match field {
+            PartField::X => range.x = values,
+            PartField::M => range.m = values,
+            PartField::A => range.a = values,
+            PartField::S => range.s = values,
+        }
+        range
+    }
+
+    fn is_empty(&self) -> bool {
+        [&self.x, &self.m, &self.a, &self.s].iter().any(|values| values.is_empty())
     }
 }
 
@@ -342,7 +318,7 @@ pub fn part2(input: &str) -> Result<usize> {
         }
         workflows.insert(wf.parse()?);
     }
-    let start: &WorkFlow = workflows.get(&"in".to_string()).context("Could not find starting workflow")?;
+    let start: &WorkFlow = workflows.get("in").context("Could not find starting workflow")?;
     let accepted_ranges = start.accepts_range(PartRange::default(), &workflows)?;
     Ok(accepted_ranges.into_iter().map(usize::from).sum())
 }
@@ -351,35 +327,54 @@ pub fn part2(input: &str) -> Result<usize> {
 mod tests {
     use super::*;
 
-    const EXAMPLE: &str = "px{a<2006:qkq,m>2090:A,rfg}
-pv{a>1716:R,A}
-lnx{m>1548:A,A}
-rfg{s<537:gd,x>2440:R,A}
-qs{s>3448:A,lnx}
-qkq{x<1416:A,crn}
-crn{x>2662:A,R}
-in{s<1351:px,qqz}
-qqz{s>2770:qs,m<1801:hdj,R}
-gd{a>3333:R,R}
-hdj{m>838:A,pv}
-
-{x=787,m=2655,a=1222,s=2876}
-{x=1679,m=44,a=2067,s=496}
-{x=2036,m=264,a=79,s=2244}
-{x=2461,m=1339,a=466,s=291}
-{x=2127,m=1623,a=2188,s=1013}
-";
-
     #[test]
-    fn test_example() {
-        assert_eq!(part1(EXAMPLE).unwrap(), 19114);
-        assert_eq!(part2(EXAMPLE).unwrap(), 167409079868000);
-    }
-
-    #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day19.txt").unwrap();
-        assert_eq!(part1(&input).unwrap(), 432788);
-        assert_eq!(part2(&input).unwrap(), 142863718918201);
+    fn test_accepts_range_agrees_with_accepts() {
+        // Ratings from 1 to BOUND, so that every part can be checked one at a time
+        const BOUND: usize = 6;
+        crate::generate::check_cases(100, |rng| {
+            let mut lines = Vec::new();
+            let count = rng.range(1..8);
+            for i in 0..count {
+                let mut rules: Vec<String> = (0..rng.range(0..4))
+                    .map(|_| {
+                        let (field, operator) = (*rng.choose(b"xmas") as char, *rng.choose(b"<>") as char);
+                        let target = if i + 1 < count && rng.chance(0.5) {
+                            format!("w{}", rng.range(i + 1..count))
+                        } else {
+                            rng.choose(&["A", "R"]).to_string()
+                        };
+                        format!("{field}{operator}{}:{target}", rng.range(0..BOUND + 2))
+                    })
+                    .collect();
+                rules.push(rng.choose(&["A", "R"]).to_string());
+                let name = if i == 0 { "in".to_string() } else { format!("w{i}") };
+                lines.push(format!("{name}{{{}}}", rules.join(",")));
+            }
+            let input = lines.join("\n");
+            let workflows: HashSet<WorkFlow> = input.lines().map(|l| l.parse().unwrap()).collect();
+            let start = workflows.get("in").unwrap();
+
+            let ratings = 1..BOUND + 1;
+            let mut one_at_a_time = 0;
+            for x in ratings.clone() {
+                for m in ratings.clone() {
+                    for a in ratings.clone() {
+                        for s in ratings.clone() {
+                            if start.accepts(&Part { x, m, a, s }, &workflows).unwrap() {
+                                one_at_a_time += 1;
+                            }
+                        }
+                    }
+                }
+            }
+            let bounded = PartRange {
+                x: ratings.clone().into(),
+                m: ratings.clone().into(),
+                a: ratings.clone().into(),
+                s: ratings.into(),
+            };
+            let accepted: usize = start.accepts_range(bounded, &workflows).unwrap().into_iter().map(usize::from).sum();
+            assert_eq!(accepted, one_at_a_time, "{input}");
+        });
     }
 }
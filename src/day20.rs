@@ -1,8 +1,11 @@
-use anyhow::{Context, Error, Result};
+use anyhow::{bail, Context, Error, Result};
+use num::Integer;
 use std::borrow::Borrow;
 use std::collections::{HashMap, VecDeque};
 use std::hash::{Hash, Hasher};
 
+use crate::{cycle::chinese_remainder, parse::Locate, render::Rgb, visualize::Frame};
+
 #[derive(Eq, PartialEq)]
 enum ModuleKind<'a> {
     FlipFlop(bool),
@@ -21,7 +24,7 @@ impl<'a> From<char> for ModuleKind<'a> {
 }
 
 #[derive(Eq, PartialEq)]
-struct Module<'a> {
+pub struct Module<'a> {
     kind: ModuleKind<'a>,
     label: &'a str,
     outputs: Vec<&'a str>,
@@ -46,8 +49,9 @@ impl<'a> TryFrom<&'a str> for Module<'a> {
     type Error = Error;
 
     fn try_from(input: &'a str) -> Result<Self> {
-        let (raw_label, raw_outputs) = input.split_once(" -> ").context("Could not split label and outputs")?;
-        let kind = ModuleKind::from(raw_label.chars().next().context("no characters")?);
+        let (raw_label, raw_outputs) =
+            input.split_once(" -> ").context("Could not split label and outputs").at(input)?;
+        let kind = ModuleKind::from(raw_label.chars().next().context("no characters").at(input)?);
         let mut label_characters = raw_label.chars();
         if kind != ModuleKind::BroadCaster {
             // Broadcaster does not have a starting symbol, but otherwise we need to trim it from the label
@@ -101,6 +105,15 @@ fn push_button<'a>(modules: &mut HashMap<&'a str, Module<'a>>) -> Result<(usize,
 fn clear_queue<'a>(
     queue: &mut VecDeque<Pulse<'a>>,
     modules: &mut HashMap<&'a str, Module<'a>>,
+) -> Result<(usize, usize)> {
+    clear_queue_with(queue, modules, |_, _| ())
+}
+
+/// The same as `clear_queue`, but calls `on_pulse` with each pulse once its receiver has handled it
+fn clear_queue_with<'a>(
+    queue: &mut VecDeque<Pulse<'a>>,
+    modules: &mut HashMap<&'a str, Module<'a>>,
+    mut on_pulse: impl FnMut(&Pulse<'a>, &HashMap<&'a str, Module<'a>>),
 ) -> Result<(usize, usize)> {
     let mut low = 0;
     let mut high = 0;
@@ -115,12 +128,13 @@ fn clear_queue<'a>(
         if let Some(module) = modules.get_mut(&pulse.receiver) {
             module.receive(&pulse, queue);
         }
+        on_pulse(&pulse, modules);
     }
 
     Ok((low, high))
 }
 
-fn parse_input<'a>(input: &'a str) -> Result<HashMap<&'a str, Module<'a>>> {
+pub fn parse_input<'a>(input: &'a str) -> Result<HashMap<&'a str, Module<'a>>> {
     let mut modules = HashMap::default();
     for l in input.lines() {
         let module: Module = l.try_into()?;
@@ -171,42 +185,163 @@ pub fn part1(input: &str) -> Result<usize> {
     Ok(low * high)
 }
 
+/// How many times to press the button while waiting for each input of the module that feeds rx to repeat
+const MAX_PRESSES: usize = 100_000;
+
+/// The presses (counting from 1) on which each input of `hub` sends it a high pulse, stopping once every input has
+/// done so three times
+fn high_pulse_presses<'a>(
+    modules: &mut HashMap<&'a str, Module<'a>>,
+    hub: &'a str,
+    inputs: Vec<&'a str>,
+) -> Result<HashMap<&'a str, Vec<usize>>> {
+    let mut presses: HashMap<&str, Vec<usize>> = inputs.into_iter().map(|input| (input, Vec::new())).collect();
+    for press in 1..=MAX_PRESSES {
+        let mut queue = [Pulse { sender: "button", receiver: "broadcaster", is_high: false }].into();
+        clear_queue_with(&mut queue, modules, |pulse, _| {
+            if pulse.receiver == hub && pulse.is_high {
+                let input_presses = presses.get_mut(pulse.sender).expect("only inputs send pulses to the hub");
+                if input_presses.last() != Some(&press) {
+                    input_presses.push(press);
+                }
+            }
+        })?;
+        if presses.values().all(|p| p.len() >= 3) {
+            break;
+        }
+    }
+    Ok(presses)
+}
+
+/// rx is fed by a single conjunction, which sends it a low pulse once all of its inputs have sent it a high pulse
+/// in the same press. Each input does so periodically, so the first press where they line up is found with the
+/// chinese remainder theorem (which is just their LCM when the cycles start at press 0).
 pub fn part2(input: &str) -> Result<usize> {
-    let modules = parse_input(input)?;
-    let [rxin] = modules.values().filter(|m| m.outputs.contains(&"rx")).map(|m| m.label).collect::<Vec<_>>()[..] else {
-        panic!("Expected a single input value to rx");
+    let mut modules = parse_input(input)?;
+    let [hub] = modules.values().filter(|m| m.outputs.contains(&"rx")).map(|m| m.label).collect::<Vec<_>>()[..] else {
+        bail!("Expected a single module to send pulses to rx");
+    };
+    let ModuleKind::Conjunction(inputs) = &modules[hub].kind else {
+        bail!("Expected {hub}, which sends pulses to rx, to be a conjunction");
+    };
+    let inputs: Vec<&str> = inputs.keys().copied().collect();
+    if inputs.is_empty() {
+        bail!("{hub}, which sends pulses to rx, has no inputs so never sends a low pulse");
+    }
+
+    let mut congruence: (i128, i128) = (0, 1);
+    let mut lead_in = 0;
+    for (input, presses) in high_pulse_presses(&mut modules, hub, inputs)? {
+        let [first, second, third, ..] = presses[..] else {
+            bail!("{input} did not send {hub} a high pulse three times in {MAX_PRESSES} presses");
+        };
+        let period = second - first;
+        if third - second != period {
+            bail!("{input} sends {hub} high pulses at presses {first}, {second} and {third}, which is not periodic");
+        }
+        congruence = chinese_remainder(congruence, (first as i128, period as i128))
+            .with_context(|| format!("The inputs to {hub} never all send it a high pulse in the same press"))?;
+        lead_in = lead_in.max(first as i128);
+    }
+    let (residue, modulus) = congruence;
+    let presses = Integer::div_ceil(&(lead_in - residue).max(0), &modulus)
+        .checked_mul(modulus)
+        .and_then(|lead_in| lead_in.checked_add(residue))
+        .context("The number of presses is too large")?;
+    usize::try_from(presses).context("The number of presses is too large")
+}
+
+/// One line per module, showing its state and its outputs
+fn describe(module: &Module) -> String {
+    let state = match &module.kind {
+        ModuleKind::BroadCaster => String::new(),
+        ModuleKind::FlipFlop(is_on) => format!("%{}", if *is_on { "on " } else { "off" }),
+        ModuleKind::Conjunction(previous_pulses) => {
+            let mut inputs: Vec<_> = previous_pulses.iter().collect();
+            inputs.sort();
+            format!("&{}", inputs.into_iter().map(|(_, &is_high)| if is_high { '1' } else { '0' }).collect::<String>())
+        }
     };
-    todo!()
+    format!("{:<12} {state:<12} -> {}", module.label, module.outputs.join(", "))
+}
+
+/// Part 1's button presses, one pulse at a time, with the sender in yellow and the receiver in green (for a high
+/// pulse) or red (for a low one)
+pub fn visualize(input: &str, _part: u8, on_frame: &mut dyn FnMut(Frame)) -> Result<()> {
+    let mut modules = parse_input(input)?;
+    let mut labels: Vec<&str> = modules.keys().copied().collect();
+    labels.sort();
+    let (mut low, mut high) = (0, 0);
+    for press in 1..=1000 {
+        let mut queue = [Pulse { sender: "button", receiver: "broadcaster", is_high: false }].into();
+        let (l, h) = clear_queue_with(&mut queue, &mut modules, |pulse, modules| {
+            let lines: Vec<String> = labels.iter().map(|label| describe(&modules[label])).collect();
+            let caption = format!(
+                "Press {press}: {} -{}-> {}",
+                pulse.sender,
+                if pulse.is_high { "high" } else { "low" },
+                pulse.receiver
+            );
+            let Ok(mut frame) = Frame::from_lines(&lines, Rgb::GREY, caption) else {
+                return;
+            };
+            for (i, &label) in labels.iter().enumerate() {
+                let colour = match label {
+                    _ if label == pulse.receiver && pulse.is_high => Rgb::GREEN,
+                    _ if label == pulse.receiver => Rgb::RED,
+                    _ if label == pulse.sender => Rgb::YELLOW,
+                    _ => continue,
+                };
+                for j in 0..frame.cells.width() {
+                    frame.cells[(i, j)].1 = colour;
+                }
+            }
+            on_frame(frame);
+        })?;
+        low += l;
+        high += h;
+        on_frame(Frame::from_lines(
+            &labels.iter().map(|label| describe(&modules[label])).collect::<Vec<_>>(),
+            Rgb::GREY,
+            format!("After {press} presses: {low} low pulses and {high} high pulses"),
+        )?);
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE: &str = "broadcaster -> a, b, c
-%a -> b
-%b -> c
-%c -> inv
-&inv -> a
-";
-
-    const EXAMPLE2: &str = "broadcaster -> a
-%a -> inv, con
-&inv -> b
-%b -> con
-&con -> output
-";
+    /// Presses the button until rx gets a low pulse
+    fn brute_force_part2(input: &str) -> usize {
+        let mut modules = parse_input(input).unwrap();
+        for press in 1.. {
+            let mut rx_low = false;
+            let mut queue = [Pulse { sender: "button", receiver: "broadcaster", is_high: false }].into();
+            clear_queue_with(&mut queue, &mut modules, |pulse, _| rx_low |= pulse.receiver == "rx" && !pulse.is_high)
+                .unwrap();
+            if rx_low {
+                return press;
+            }
+        }
+        unreachable!()
+    }
 
     #[test]
-    fn test_example() {
-        assert_eq!(part1(EXAMPLE).unwrap(), 32000000);
-        assert_eq!(part1(EXAMPLE2).unwrap(), 11687500);
+    fn test_part2_agrees_with_brute_force() {
+        // A single counter, so that it takes at most a few thousand presses
+        crate::generate::check_cases(10, |rng| {
+            let input = crate::generate::input(20, rng.next_u64(), 1).unwrap();
+            assert_eq!(part2(&input).unwrap(), brute_force_part2(&input), "{input}");
+        });
     }
 
     #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day20.txt").unwrap();
-        assert_eq!(part1(&input).unwrap(), 747304011);
-        assert_eq!(part2(&input).unwrap(), 0);
+    fn test_part2_needs_rx() {
+        let error = part2("broadcaster -> a\n%a -> b\n&b -> a\n").unwrap_err();
+        assert_eq!(error.to_string(), "Expected a single module to send pulses to rx");
+        let error = part2("broadcaster -> a\n%a -> a\n&hub -> rx\n").unwrap_err();
+        assert_eq!(error.to_string(), "hub, which sends pulses to rx, has no inputs so never sends a low pulse");
     }
 }
@@ -1,199 +1,217 @@
 use anyhow::{bail, Context, Error, Result};
-use std::{collections::HashMap, str::FromStr};
+use std::{collections::VecDeque, str::FromStr};
 
+use crate::{
+    grid::{Grid, Posn},
+    render::{self, Image, Rgb},
+};
+
+#[derive(PartialEq, Eq)]
 enum Tile {
     Garden,
     Rock,
+    /// The garden plot the elf starts on
+    Start,
 }
 
-type Posn = (usize, usize);
-
-struct Map {
-    start: Posn,
-    tiles: Vec<Vec<Tile>>,
+impl TryFrom<char> for Tile {
+    type Error = Error;
+    fn try_from(value: char) -> Result<Self> {
+        Ok(match value {
+            '.' => Self::Garden,
+            '#' => Self::Rock,
+            'S' => Self::Start,
+            _ => bail!("Unexpected tile (should be '.', '#', or 'S'): {value}"),
+        })
+    }
 }
 
-enum Wrapping {
-    Up,
-    Down,
-    Left,
-    Right,
+pub struct Map {
+    start: Posn,
+    tiles: Grid<Tile>,
 }
 
 impl FromStr for Map {
     type Err = Error;
     fn from_str(input: &str) -> Result<Self> {
-        let mut start: Option<Posn> = None;
-        let mut tiles: Vec<Vec<Tile>> = Vec::new();
-        for l in input.lines() {
-            let mut row: Vec<Tile> = Vec::new();
-            for c in l.chars() {
-                match c {
-                    '.' => row.push(Tile::Garden),
-                    '#' => row.push(Tile::Rock),
-                    'S' => {
-                        if start.is_some() {
-                            bail!("multiple start tiles parsed");
-                        }
-                        start = Some((tiles.len(), row.len()));
-                        row.push(Tile::Garden);
-                    }
-                    _ => bail!("Unexpected tile (should be '.', '#', or 'S'): {c}"),
-                }
-            }
-            tiles.push(row);
+        let tiles: Grid<Tile> = input.parse()?;
+        let starts: Vec<Posn> = tiles.iter().filter(|(_, tile)| tile == &&Tile::Start).map(|(posn, _)| posn).collect();
+        let start = *starts.first().context("Did not find the start tile")?;
+        if starts.len() > 1 {
+            bail!("multiple start tiles parsed");
         }
-        Ok(Self { tiles, start: start.context("Did not find the start tile")? })
+        Ok(Self { tiles, start })
     }
 }
 
 impl Map {
+    fn is_garden(&self, posn: Posn) -> bool {
+        self.tiles[posn] != Tile::Rock
+    }
+
     fn successors(&self, posn: &Posn) -> Vec<Posn> {
-        let mut successors: Vec<Posn> = Vec::new();
-        if posn.0 != 0 {
-            if let Tile::Garden = self.tiles[posn.0 - 1][posn.1] {
-                successors.push((posn.0 - 1, posn.1));
-            }
-        }
-        if posn.0 != self.tiles.len() - 1 {
-            if let Tile::Garden = self.tiles[posn.0 + 1][posn.1] {
-                successors.push((posn.0 + 1, posn.1));
-            }
-        }
-        if posn.1 != 0 {
-            if let Tile::Garden = self.tiles[posn.0][posn.1 - 1] {
-                successors.push((posn.0, posn.1 - 1));
-            }
-        }
-        if posn.1 != self.tiles[posn.0].len() - 1 {
-            if let Tile::Garden = self.tiles[posn.0][posn.1 + 1] {
-                successors.push((posn.0, posn.1 + 1));
-            }
-        }
-        successors
+        self.tiles.neighbours4(*posn).filter(|&neighbour| self.is_garden(neighbour)).collect()
     }
 
-    fn successors_with_wrapping(&self, posn: &Posn) -> Vec<(Posn, Option<Wrapping>)> {
-        let mut successors: Vec<(Posn, Option<Wrapping>)> = Vec::new();
-        if posn.0 != 0 {
-            if let Tile::Garden = self.tiles[posn.0 - 1][posn.1] {
-                successors.push(((posn.0 - 1, posn.1), None));
-            }
-        } else {
-            if let Tile::Garden = self.tiles[self.tiles.len() - 1][posn.1] {
-                successors.push(((self.tiles.len() - 1, posn.1), Some(Wrapping::Up)));
-            }
-        }
-        if posn.0 != self.tiles.len() - 1 {
-            if let Tile::Garden = self.tiles[posn.0 + 1][posn.1] {
-                successors.push(((posn.0 + 1, posn.1), None));
-            }
-        } else {
-            if let Tile::Garden = self.tiles[0][posn.1] {
-                successors.push(((0, posn.1), Some(Wrapping::Down)));
-            }
-        }
-        if posn.1 != 0 {
-            if let Tile::Garden = self.tiles[posn.0][posn.1 - 1] {
-                successors.push(((posn.0, posn.1 - 1), None));
-            }
-        } else {
-            if let Tile::Garden = self.tiles[posn.0][self.tiles[posn.0].len() - 1] {
-                successors.push(((posn.0, self.tiles[posn.0].len() - 1), Some(Wrapping::Left)));
-            }
-        }
-        if posn.1 != self.tiles[posn.0].len() - 1 {
-            if let Tile::Garden = self.tiles[posn.0][posn.1 + 1] {
-                successors.push(((posn.0, posn.1 + 1), None));
-            }
-        } else {
-            if let Tile::Garden = self.tiles[posn.0][0] {
-                successors.push(((posn.0, 0), Some(Wrapping::Right)));
+    /// The fewest steps to each plot of a square of copies of the map, reaching `copies` copies out from the one with
+    /// the start in the middle
+    fn distances_on_copies(&self, copies: usize) -> Grid<Option<usize>> {
+        let (height, width) = (self.tiles.height(), self.tiles.width());
+        let mut distances = Grid::new((2 * copies + 1) * width, (2 * copies + 1) * height, None);
+        let start = (copies * height + self.start.0, copies * width + self.start.1);
+        distances[start] = Some(0);
+        let mut queue = VecDeque::from([(start, 0)]);
+        while let Some((posn, distance)) = queue.pop_front() {
+            for next in distances.neighbours4(posn).collect::<Vec<_>>() {
+                if distances[next].is_none() && self.is_garden((next.0 % height, next.1 % width)) {
+                    distances[next] = Some(distance + 1);
+                    queue.push_back((next, distance + 1));
+                }
             }
         }
-        successors
+        distances
     }
 }
 
-pub fn part1(input: &str, steps: usize) -> Result<usize> {
-    let map: Map = input.parse()?;
+fn reachable(map: &Map, steps: usize) -> Vec<Posn> {
     let mut reachable: Vec<Posn> = vec![map.start];
     for _ in 0..steps {
         reachable = reachable.into_iter().flat_map(|s| map.successors(&s)).collect();
         reachable.sort_unstable();
         reachable.dedup();
     }
-    Ok(reachable.len())
+    reachable
 }
 
-pub fn part2(input: &str, steps: usize) -> Result<usize> {
-    // Since we can always move away from a plot and then back on to it, once we have reached
-    // a plot in an even number of steps, we can reach it any any subsequent even number of steps.
-    // The same goes for odd numbers: any subsequent odd number of steps we can be at the plot.
-    // Thus finding the minimum odd and even number of steps to reach a garden plot is crucial:
-    // Any number of steps after this point will also be able to reach that plot.
-    // There should be many copies of the map that we can reach completely
-    // Then, for the "edge cases", we just need to figure out how much of them can be filled
-    //
+pub fn part1(input: &str, steps: usize) -> Result<usize> {
+    Ok(reachable(&input.parse()?, steps).len())
+}
 
+/// The garden plots reachable in exactly `steps` steps in yellow, the other plots in green, and rocks in grey
+pub fn render(input: &str, steps: usize) -> Result<Image> {
     let map: Map = input.parse()?;
-    let mut reachable: Vec<(Posn, isize, isize)> = vec![(map.start, 0, 0)];
-    for _ in 0..steps {
-        reachable = reachable
-            .into_iter()
-            .flat_map(|(posn, garden_i, garden_j)| {
-                map.successors_with_wrapping(&posn).into_iter().map(move |(neighbour, wrapping)| match wrapping {
-                    Some(Wrapping::Up) => (neighbour, garden_i - 1, garden_j),
-                    Some(Wrapping::Down) => (neighbour, garden_i + 1, garden_j),
-                    Some(Wrapping::Left) => (neighbour, garden_i, garden_j - 1),
-                    Some(Wrapping::Right) => (neighbour, garden_i, garden_j + 1),
-                    None => (neighbour, garden_i, garden_j),
-                })
-            })
-            .collect();
-        reachable.sort_unstable();
-        reachable.dedup();
+    let mut image = map.tiles.map(|tile| if tile == &Tile::Rock { Rgb::DARK_GREY } else { Rgb::GREEN });
+    render::overlay(&mut image, reachable(&map, steps), Rgb::YELLOW);
+    Ok(image)
+}
+
+/// How many copies of the map to search in each direction before assuming that each copy further out is exactly
+/// one map's width further away
+const SEARCHED_COPIES: usize = 4;
+
+/// Which copies of a plot in the outermost searched copies stand in for the copies beyond them
+#[derive(Clone, Copy)]
+enum Repeats {
+    None,
+    /// One copy each map's width further away along an axis
+    Axis,
+    /// k + 1 copies at k map widths further away, filling in a quadrant
+    Diagonal,
+}
+
+/// How many copies of a plot that is `distance` steps away can be reached in exactly `steps` steps.
+/// Stepping off a plot and back on again takes two steps, so a copy can be reached if it is close enough and its
+/// distance has the same parity as `steps`.
+fn copies_reached(distance: usize, steps: usize, width: usize, repeats: Repeats) -> usize {
+    let Some(spare) = steps.checked_sub(distance) else {
+        return 0;
+    };
+    // The copies k widths further away with the right parity are first, first + stride, ... up to furthest
+    let (first, stride) = match (width % 2, spare % 2) {
+        (0, 0) => (0, 1),
+        (0, _) => return 0,
+        (_, parity) => (parity, 2),
+    };
+    let furthest = spare / width;
+    if first > furthest {
+        return 0;
+    }
+    let count = (furthest - first) / stride + 1;
+    match repeats {
+        Repeats::None => usize::from(first == 0),
+        Repeats::Axis => count,
+        Repeats::Diagonal => count * (first + 1) + stride * count * (count - 1) / 2,
+    }
+}
+
+/// The plots reachable on an infinite plane tiled with copies of the map. Distances to every plot in the nearest
+/// copies come from a breadth-first search, and the copies further out are counted from the outermost searched ones.
+pub fn part2(input: &str, steps: usize) -> Result<usize> {
+    let map: Map = input.parse()?;
+    let width = map.tiles.width();
+    if map.tiles.height() != width {
+        bail!("Expected a square map, but it is {} by {width}", map.tiles.height());
     }
-    Ok(reachable.len())
+    let distances = map.distances_on_copies(SEARCHED_COPIES);
+    let is_outermost = |i: usize| i / width == 0 || i / width == 2 * SEARCHED_COPIES;
+    Ok(distances
+        .iter()
+        .filter_map(|((i, j), distance)| {
+            let repeats = match (is_outermost(i), is_outermost(j)) {
+                (true, true) => Repeats::Diagonal,
+                (true, false) | (false, true) => Repeats::Axis,
+                (false, false) => Repeats::None,
+            };
+            distance.map(|d| copies_reached(d, steps, width, repeats))
+        })
+        .sum())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        examples,
+        point::{Direction, Point},
+    };
 
-    const EXAMPLE: &str = "...........
-.....###.#.
-.###.##..#.
-..#.#...#..
-....#.#....
-.##..S####.
-.##..#...#.
-.......##..
-.##.#.####.
-.##..##.##.
-...........
-";
+    /// Like `Map::successors`, but on an infinite plane tiled with copies of the map
+    fn successors_with_wrapping(map: &Map, point: Point) -> Vec<Point> {
+        let (height, width) = (map.tiles.height() as isize, map.tiles.width() as isize);
+        Direction::ALL
+            .into_iter()
+            .map(|direction| point + direction)
+            .filter(|next| {
+                let wrapped = (next.row.rem_euclid(height) as usize, next.column.rem_euclid(width) as usize);
+                map.is_garden(wrapped)
+            })
+            .collect()
+    }
 
-    #[test]
-    fn test_example_part1() {
-        assert_eq!(part1(EXAMPLE, 6).unwrap(), 16);
+    /// Steps every reachable plot on the infinite plane, which is only fast enough for a few hundred steps
+    fn brute_force_part2(input: &str, steps: usize) -> usize {
+        let map: Map = input.parse().unwrap();
+        let mut reachable: Vec<Point> = vec![map.start.into()];
+        for _ in 0..steps {
+            reachable = reachable.into_iter().flat_map(|point| successors_with_wrapping(&map, point)).collect();
+            reachable.sort_unstable();
+            reachable.dedup();
+        }
+        reachable.len()
     }
 
     #[test]
     fn test_example_part2() {
-        assert_eq!(part2(EXAMPLE, 6).unwrap(), 16);
-        assert_eq!(part2(EXAMPLE, 10).unwrap(), 50);
-        assert_eq!(part2(EXAMPLE, 50).unwrap(), 1594);
-        assert_eq!(part2(EXAMPLE, 100).unwrap(), 6536);
-        // assert_eq!(part2(EXAMPLE, 500).unwrap(), 167004);
-        // assert_eq!(part2(EXAMPLE, 1000).unwrap(), 668697);
-        // assert_eq!(part2(EXAMPLE, 5000).unwrap(), 16733044);
+        let input = examples::input(21, "example");
+        assert_eq!(part2(&input, 6).unwrap(), 16);
+        assert_eq!(part2(&input, 10).unwrap(), 50);
+        assert_eq!(part2(&input, 50).unwrap(), 1594);
+        assert_eq!(part2(&input, 100).unwrap(), 6536);
+        assert_eq!(part2(&input, 500).unwrap(), 167004);
+        assert_eq!(part2(&input, 1000).unwrap(), 668697);
+        assert_eq!(part2(&input, 5000).unwrap(), 16733044);
     }
 
     #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day21.txt").unwrap();
-        assert_eq!(part1(&input, 64).unwrap(), 3748);
-        // assert_eq!(part2(&input, 26501365).unwrap(), 0);
+    fn test_part2_agrees_with_brute_force() {
+        let example = examples::input(21, "example");
+        for steps in [0, 1, 7, 33, 64, 101, 150] {
+            assert_eq!(part2(&example, steps).unwrap(), brute_force_part2(&example, steps), "{steps} steps");
+        }
+        crate::generate::check_cases(10, |rng| {
+            let input = crate::generate::input(21, rng.next_u64(), 2 * rng.range(3..8) + 1).unwrap();
+            let steps = rng.range(0..60);
+            assert_eq!(part2(&input, steps).unwrap(), brute_force_part2(&input, steps), "{steps} steps on\n{input}");
+        });
     }
 }
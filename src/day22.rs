@@ -1,7 +1,9 @@
-use std::{str::FromStr, collections::HashSet};
+use std::{collections::HashSet, str::FromStr};
 
 use anyhow::{anyhow, Context, Error, Result};
 
+use crate::{grid::Grid, parse::Locate, render::Rgb, visualize::Frame};
+
 #[derive(Eq, PartialEq, Clone, Hash)]
 struct Posn {
     x: usize,
@@ -13,20 +15,20 @@ impl FromStr for Posn {
     type Err = Error;
     fn from_str(input: &str) -> Result<Self> {
         if let [x, y, z] = input.split(',').collect::<Vec<_>>()[..] {
-            Ok(Self { x: x.parse()?, y: y.parse()?, z: z.parse()? })
+            Ok(Self { x: x.parse().at(x)?, y: y.parse().at(y)?, z: z.parse().at(z)? })
         } else {
-            Err(anyhow!("Could not split the input into 3 comma separated parts: {input}"))
+            Err(anyhow!("Could not split the input into 3 comma separated parts: {input}")).at(input)
         }
     }
 }
 
 #[derive(Clone, PartialEq, Eq)]
-struct Brick(Posn, Posn);
+pub struct Brick(Posn, Posn);
 
 impl FromStr for Brick {
     type Err = Error;
     fn from_str(input: &str) -> Result<Self> {
-        let (a, b) = input.split_once('~').context("Could not split the input into tilde separated posns")?;
+        let (a, b) = input.split_once('~').context("Could not split the input into tilde separated posns").at(input)?;
         Ok(Self(a.parse()?, b.parse()?))
     }
 }
@@ -58,12 +60,18 @@ impl Brick {
     }
 }
 
-fn settle(bricks: &mut [Brick]){
-    bricks.sort_by(|a, b| a.0.z.cmp(&b.0.z));
+fn settle(bricks: &mut [Brick]) {
+    settle_with(bricks, |_, _| ());
+}
+
+/// The same as `settle`, but calls `on_settle` with every brick and the index of the brick that has just settled
+fn settle_with(bricks: &mut [Brick], mut on_settle: impl FnMut(&[Brick], usize)) {
+    bricks.sort_by_key(|b| b.0.z);
 
     let mut settled_cover: HashSet<Posn> = HashSet::default();
 
-    for brick in bricks.iter_mut() {
+    for i in 0..bricks.len() {
+        let brick = &mut bricks[i];
         loop {
             if brick.is_grounded() {
                 break;
@@ -77,17 +85,17 @@ fn settle(bricks: &mut [Brick]){
                 brick.1.z += 1;
                 break;
             }
-
         }
 
         for p in brick.all() {
             settled_cover.insert(p);
         }
+        on_settle(bricks, i);
     }
 }
 
 fn num_unsettled(bricks: &mut [Brick]) -> usize {
-    bricks.sort_by(|a, b| a.0.z.cmp(&b.0.z));
+    bricks.sort_by_key(|b| b.0.z);
 
     let mut settled_cover: HashSet<Posn> = HashSet::default();
 
@@ -121,14 +129,18 @@ fn num_unsettled(bricks: &mut [Brick]) -> usize {
     count
 }
 
-pub fn part1(input: &str) -> Result<usize> {
+pub fn parse_input(input: &str) -> Result<Vec<Brick>> {
     let mut bricks: Vec<Brick> = Vec::new();
     for l in input.lines() {
         bricks.push(l.parse()?);
     }
+    Ok(bricks)
+}
+
+pub fn part1(input: &str) -> Result<usize> {
+    let mut bricks = parse_input(input)?;
     settle(&mut bricks);
 
-    // 10s, not great
     let mut could_disintegrate = 0;
     for i in 0..bricks.len() {
         let mut possibly_settled = bricks.clone();
@@ -142,13 +154,9 @@ pub fn part1(input: &str) -> Result<usize> {
 }
 
 pub fn part2(input: &str) -> Result<usize> {
-    let mut bricks: Vec<Brick> = Vec::new();
-    for l in input.lines() {
-        bricks.push(l.parse()?);
-    }
+    let mut bricks = parse_input(input)?;
     settle(&mut bricks);
 
-    // 10s, not great
     let mut total = 0;
     for i in 0..bricks.len() {
         let mut possibly_settled = bricks.clone();
@@ -159,29 +167,60 @@ pub fn part2(input: &str) -> Result<usize> {
     Ok(total)
 }
 
+const PALETTE: [Rgb; 6] =
+    [Rgb(230, 80, 80), Rgb(80, 200, 120), Rgb(90, 140, 240), Rgb(240, 200, 60), Rgb(200, 100, 220), Rgb(80, 210, 220)];
+
+/// The bricks seen from the front (x against z) and from the side (y against z), with the nearest brick in front.
+/// Bricks that have settled are coloured, the brick that has just settled is white, and the rest are grey.
+fn frame(bricks: &[Brick], settled: usize, caption: String) -> Frame {
+    let top = bricks.iter().map(|b| b.1.z).max().unwrap_or(0);
+    let width = bricks.iter().map(|b| b.1.x).max().unwrap_or(0) + 1;
+    let depth = bricks.iter().map(|b| b.1.y).max().unwrap_or(0) + 1;
+    let mut cells = Grid::new(width + 3 + depth, top + 2, (' ', Rgb::BLACK));
+    let mut nearest = cells.map(|_| usize::MAX);
+    for (i, brick) in bricks.iter().enumerate() {
+        let colour = match i {
+            _ if i == settled => Rgb::WHITE,
+            _ if i < settled => PALETTE[i % PALETTE.len()],
+            _ => Rgb::DARK_GREY,
+        };
+        for p in brick.all() {
+            // Looking along y for the front view, and along x for the side view
+            for (column, distance) in [(p.x, p.y), (width + 3 + p.y, p.x)] {
+                let posn = (top - p.z, column);
+                if distance < nearest[posn] {
+                    nearest[posn] = distance;
+                    cells[posn] = ('#', colour);
+                }
+            }
+        }
+    }
+    for j in (0..width).chain(width + 3..width + 3 + depth) {
+        cells[(top + 1, j)] = ('-', Rgb::GREY);
+    }
+    Frame { cells, caption }
+}
+
+/// The bricks falling into place one at a time
+pub fn visualize(input: &str, _part: u8, on_frame: &mut dyn FnMut(Frame)) -> Result<()> {
+    let mut bricks = parse_input(input)?;
+    let count = bricks.len();
+    settle_with(&mut bricks, |bricks, i| {
+        on_frame(frame(bricks, i, format!("{} of {count} bricks settled", i + 1)));
+    });
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    const EXAMPLE: &str = "1,0,1~1,2,1
-0,0,2~2,0,2
-0,2,3~2,2,3
-0,0,4~0,2,4
-2,0,5~2,2,5
-0,1,6~2,1,6
-1,1,8~1,1,9
-";
-
-    #[test]
-    fn test_example() {
-        assert_eq!(part1(EXAMPLE).unwrap(), 5);
-        assert_eq!(part2(EXAMPLE).unwrap(), 7);
-    }
+    use crate::examples;
 
     #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day22.txt").unwrap();
-        assert_eq!(part1(&input).unwrap(), 507);
-        assert_eq!(part2(&input).unwrap(), 51733);
+    fn test_settle_with() {
+        let mut bricks = parse_input(&examples::input(22, "example")).unwrap();
+        let mut settled = Vec::new();
+        settle_with(&mut bricks, |bricks, i| settled.push((i, bricks[i].0.z)));
+        assert_eq!(settled, [(0, 0), (1, 1), (2, 1), (3, 2), (4, 2), (5, 3), (6, 4)]);
     }
 }
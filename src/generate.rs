@@ -0,0 +1,883 @@
+use anyhow::{bail, Result};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write as _,
+    ops::Range,
+};
+
+/// A small seedable random number generator (SplitMix64), so that the same seed always gives the same input
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number from `range`, which must not be empty
+    pub fn range(&mut self, range: Range<usize>) -> usize {
+        assert!(!range.is_empty(), "Cannot pick from an empty range");
+        range.start + (self.next_u64() % range.len() as u64) as usize
+    }
+
+    /// A (possibly negative) number from `range`, which must not be empty
+    pub fn signed(&mut self, range: Range<i64>) -> i64 {
+        assert!(!range.is_empty(), "Cannot pick from an empty range");
+        range.start + (self.next_u64() % range.start.abs_diff(range.end)) as i64
+    }
+
+    /// True with probability `p`
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..i + 1));
+        }
+    }
+
+    /// A string of `len` characters from `alphabet`
+    fn word(&mut self, alphabet: &[u8], len: usize) -> String {
+        (0..len).map(|_| *self.choose(alphabet) as char).collect()
+    }
+}
+
+/// Runs `check` once for each of `cases` seeds, like a property test, reporting the seed of the case that fails so
+/// that it can be reproduced
+#[cfg(test)]
+pub(crate) fn check_cases(cases: u64, check: impl Fn(&mut Rng)) {
+    for seed in 0..cases {
+        if let Err(panic) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| check(&mut Rng::new(seed)))) {
+            eprintln!("Failed with seed {seed}");
+            std::panic::resume_unwind(panic);
+        }
+    }
+}
+
+const LOWERCASE: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+/// `count` distinct names made by `name`, which is given the length to use.
+/// Names start `min_len` long, and get longer when there are too few of that length to go round.
+fn distinct_names(
+    rng: &mut Rng,
+    count: usize,
+    min_len: usize,
+    possible: impl Fn(usize) -> usize,
+    mut name: impl FnMut(&mut Rng, usize) -> String,
+    reserved: &[&str],
+) -> Vec<String> {
+    let mut len = min_len;
+    while possible(len) < 2 * (count + reserved.len()) {
+        len += 1;
+    }
+    let mut seen: HashSet<String> = reserved.iter().map(|r| r.to_string()).collect();
+    let mut names = Vec::with_capacity(count);
+    while names.len() < count {
+        let candidate = name(rng, len);
+        if seen.insert(candidate.clone()) {
+            names.push(candidate);
+        }
+    }
+    names
+}
+
+/// Lowercase names such as "qz", at least `min_len` letters long
+fn lowercase_names(rng: &mut Rng, count: usize, min_len: usize, reserved: &[&str]) -> Vec<String> {
+    let possible = |len: usize| 26usize.saturating_pow(len as u32);
+    distinct_names(rng, count, min_len, possible, |rng, len| rng.word(LOWERCASE, len), reserved)
+}
+
+/// The size each day's input is generated at unless asked otherwise, which is roughly that of the real puzzle input.
+/// What the size counts differs from day to day, and is described on each day's generator.
+pub fn default_size(day: u8) -> usize {
+    match day {
+        1 | 2 | 7 | 9 | 12 => 1000,
+        3 | 10 | 11 | 14 | 16 | 17 => 140,
+        4 => 200,
+        5 => 40,
+        6 => 4,
+        8 => 61,
+        13 => 100,
+        15 => 4000,
+        18 => 150,
+        19 => 500,
+        20 => 4,
+        21 => 131,
+        22 => 1200,
+        23 => 6,
+        24 => 300,
+        25 => 1500,
+        _ => 0,
+    }
+}
+
+/// A random but valid puzzle input for `day`, which is always the same for the same `seed` and `size`
+pub fn input(day: u8, seed: u64, size: usize) -> Result<String> {
+    let rng = &mut Rng::new(seed);
+    Ok(match day {
+        1 => day01(rng, size.max(1)),
+        2 => day02(rng, size.max(1)),
+        3 => day03(rng, size.max(3)),
+        4 => day04(rng, size.max(1)),
+        5 => day05(rng, size.max(1)),
+        6 => day06(rng, size.clamp(1, 4)),
+        7 => day07(rng, size.clamp(1, 10_000)),
+        8 => day08(rng, size.max(1)),
+        9 => day09(rng, size.max(1)),
+        10 => day10(rng, size.max(3)),
+        11 => day11(rng, size.max(2)),
+        12 => day12(rng, size.max(1)),
+        13 => day13(rng, size.max(1)),
+        14 => day14(rng, size.max(1)),
+        15 => day15(rng, size.max(1)),
+        16 => day16(rng, size.max(1)),
+        17 => day17(rng, size.max(5)),
+        18 => day18(rng, size.max(1)),
+        19 => day19(rng, size.max(1)),
+        20 => day20(rng, size.max(1)),
+        21 => day21(rng, size.max(5) | 1),
+        22 => day22(rng, size.max(1)),
+        23 => day23(rng, size.max(2)),
+        24 => day24(rng, size.max(3)),
+        25 => day25(rng, size.max(16)),
+        _ => bail!("There is no day {day} to generate an input for"),
+    })
+}
+
+/// A grid of `height` rows, with each cell chosen by `cell`
+fn grid(height: usize, width: usize, mut cell: impl FnMut(usize, usize) -> char) -> String {
+    let mut out = String::with_capacity(height * (width + 1));
+    for i in 0..height {
+        out.extend((0..width).map(|j| cell(i, j)));
+        out.push('\n');
+    }
+    out
+}
+
+/// `size` lines of letters, digits and spelled out digits, each with at least one digit
+fn day01(rng: &mut Rng, size: usize) -> String {
+    const WORDS: [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+    let mut out = String::new();
+    for _ in 0..size {
+        let mut pieces: Vec<String> = (0..rng.range(1..7))
+            .map(|_| match rng.range(0..3) {
+                0 => {
+                    let len = rng.range(1..5);
+                    rng.word(LOWERCASE, len)
+                }
+                1 => rng.range(1..10).to_string(),
+                _ => rng.choose(&WORDS).to_string(),
+            })
+            .collect();
+        let at = rng.range(0..pieces.len() + 1);
+        pieces.insert(at, rng.range(1..10).to_string());
+        out.push_str(&pieces.concat());
+        out.push('\n');
+    }
+    out
+}
+
+/// `size` games of drawing coloured cubes
+fn day02(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for game in 1..=size {
+        let draws: Vec<String> = (0..rng.range(1..7))
+            .map(|_| {
+                let mut colours = ["red", "green", "blue"];
+                rng.shuffle(&mut colours);
+                let shown = rng.range(1..4);
+                colours[..shown].iter().map(|c| format!("{} {c}", rng.range(1..21))).collect::<Vec<_>>().join(", ")
+            })
+            .collect();
+        let _ = writeln!(out, "Game {game}: {}", draws.join("; "));
+    }
+    out
+}
+
+/// A `size` by `size` engine schematic of part numbers and symbols
+fn day03(rng: &mut Rng, size: usize) -> String {
+    let mut rows = Vec::with_capacity(size);
+    for _ in 0..size {
+        let mut row = String::with_capacity(size);
+        while row.len() < size {
+            let digits = rng.range(1..4);
+            if rng.chance(0.15) && row.len() + digits < size {
+                let _ = write!(row, "{}", rng.range(10usize.pow(digits as u32 - 1)..10usize.pow(digits as u32)));
+                row.push('.');
+            } else if rng.chance(0.05) {
+                row.push(*rng.choose(b"*#+$/=%@&-") as char);
+            } else {
+                row.push('.');
+            }
+        }
+        rows.push(row);
+    }
+    rows.join("\n") + "\n"
+}
+
+/// `size` scratchcards. Few enough cards win copies of later ones that the number of copies stays small.
+fn day04(rng: &mut Rng, size: usize) -> String {
+    let pool: Vec<usize> = (1..100).collect();
+    let mut out = String::new();
+    for card in 1..=size {
+        let mut numbers = pool.clone();
+        rng.shuffle(&mut numbers);
+        let (winning, others) = numbers.split_at(10);
+        let matches = if rng.chance(0.6) { 0 } else { rng.range(1..3) }.min(size - card);
+        let mut mine: Vec<usize> = winning[..matches].iter().chain(&others[..25 - matches]).copied().collect();
+        rng.shuffle(&mut mine);
+        let show = |ns: &[usize]| ns.iter().map(|n| format!("{n:>2}")).collect::<Vec<_>>().join(" ");
+        let _ = writeln!(out, "Card {card:>3}: {} | {}", show(winning), show(&mine));
+    }
+    out
+}
+
+/// An almanac whose maps each have `size` ranges, which between them move every number below 2^32 somewhere else
+fn day05(rng: &mut Rng, size: usize) -> String {
+    const UNIVERSE: usize = 1 << 32;
+    const MAPS: [&str; 7] = [
+        "seed-to-soil",
+        "soil-to-fertilizer",
+        "fertilizer-to-water",
+        "water-to-light",
+        "light-to-temperature",
+        "temperature-to-humidity",
+        "humidity-to-location",
+    ];
+
+    let seeds: Vec<String> = (0..10)
+        .map(|_| {
+            let len = rng.range(1..UNIVERSE / 20);
+            format!("{} {len}", rng.range(0..UNIVERSE - len))
+        })
+        .collect();
+    let mut out = format!("seeds: {}\n", seeds.join(" "));
+
+    for name in MAPS {
+        // Cut the numbers into chunks, then lay the chunks back down in a different order
+        let mut cuts: Vec<usize> = (1..size).map(|_| rng.range(1..UNIVERSE)).collect();
+        cuts.extend([0, UNIVERSE]);
+        cuts.sort_unstable();
+        cuts.dedup();
+        let mut chunks: Vec<(usize, usize)> = cuts.windows(2).map(|w| (w[0], w[1] - w[0])).collect();
+        rng.shuffle(&mut chunks);
+        let mut destination = 0;
+        let mut lines: Vec<String> = chunks
+            .iter()
+            .map(|&(source, len)| {
+                destination += len;
+                format!("{} {source} {len}", destination - len)
+            })
+            .collect();
+        rng.shuffle(&mut lines);
+        let _ = write!(out, "\n{name} map:\n{}\n", lines.join("\n"));
+    }
+    out
+}
+
+/// `size` (at most 4) races, each of which can be won, as can the single long race they make together
+fn day06(rng: &mut Rng, size: usize) -> String {
+    loop {
+        let races: Vec<(usize, usize)> = (0..size)
+            .map(|_| {
+                let time = rng.range(7..100);
+                let best = (time / 2) * (time - time / 2);
+                (time, rng.range(best / 2..best))
+            })
+            .collect();
+        let join = |f: fn(&(usize, usize)) -> usize| races.iter().map(|r| f(r).to_string()).collect::<String>();
+        let (time, distance): (u128, u128) = (join(|r| r.0).parse().unwrap(), join(|r| r.1).parse().unwrap());
+        if distance < (time / 2) * (time - time / 2) {
+            let row =
+                |f: fn(&(usize, usize)) -> usize| races.iter().map(|r| format!("{:>7}", f(r))).collect::<String>();
+            return format!("Time:    {}\nDistance:{}\n", row(|r| r.0), row(|r| r.1));
+        }
+    }
+}
+
+/// `size` distinct hands of camel cards with their bids
+fn day07(rng: &mut Rng, size: usize) -> String {
+    let mut seen = HashSet::new();
+    let mut out = String::new();
+    while seen.len() < size {
+        let hand = rng.word(b"23456789TJQKA", 5);
+        if seen.insert(hand.clone()) {
+            let _ = writeln!(out, "{hand} {}", rng.range(1..1001));
+        }
+    }
+    out
+}
+
+/// A network navigated by `size` instructions, where each ghost reaches its one end node every so many steps, which is
+/// a different prime multiple of `size` for each ghost
+fn day08(rng: &mut Rng, size: usize) -> String {
+    let instructions = rng.word(b"LR", size);
+    let mut multiples = [2, 3, 5, 7];
+    rng.shuffle(&mut multiples);
+    let ghosts = rng.range(2..5);
+
+    // Each step of a ghost's loop is a pair of nodes, either of which leads to the next pair
+    let total_nodes: usize = multiples[..ghosts].iter().map(|m| 2 * m * size).sum();
+    let alphabet = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    let possible = |len: usize| 36usize.saturating_pow(len as u32 - 1).saturating_mul(34);
+    let name = |rng: &mut Rng, len: usize| {
+        let mut name = rng.word(alphabet, len - 1);
+        name.push(*rng.choose(b"BCDEFGHIJKLMNOPQRSTUVWXY0123456789") as char);
+        name
+    };
+    let mut names = distinct_names(rng, total_nodes, 3, possible, name, &["AAA", "ZZZ"]).into_iter();
+    let prefixes = distinct_names(
+        rng,
+        ghosts,
+        2,
+        |len| 36usize.saturating_pow(len as u32),
+        |rng, len| rng.word(alphabet, len),
+        &["AA", "ZZ"],
+    );
+
+    let mut lines = Vec::new();
+    for (ghost, prefix) in prefixes.iter().enumerate() {
+        // The first ghost is also the one that part 1 follows
+        let (start, end) = match ghost {
+            0 => ("AAA".to_string(), "ZZZ".to_string()),
+            _ => (format!("{prefix}A"), format!("{prefix}Z")),
+        };
+        let length = multiples[ghost] * size;
+        let pairs: Vec<[String; 2]> = (1..length).map(|_| [0, 1].map(|_| names.next().unwrap())).collect();
+        let after = |step: usize| match pairs.get(step) {
+            Some([left, right]) => format!("({left}, {right})"),
+            None => format!("({end}, {end})"),
+        };
+        lines.push(format!("{start} = {}", after(0)));
+        lines.push(format!("{end} = {}", after(0)));
+        for (step, pair) in pairs.iter().enumerate() {
+            for node in pair {
+                lines.push(format!("{node} = {}", after(step + 1)));
+            }
+        }
+    }
+    rng.shuffle(&mut lines);
+    format!("{instructions}\n\n{}\n", lines.join("\n"))
+}
+
+/// `size` sequences of 21 values of a polynomial of degree at most 5
+fn day09(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size {
+        let coefficients: Vec<i64> = (0..rng.range(1..7)).map(|_| rng.signed(-5..6)).collect();
+        let values: Vec<String> =
+            (0..21i64).map(|x| coefficients.iter().rev().fold(0, |acc, c| acc * x + c).to_string()).collect();
+        out.push_str(&values.join(" "));
+        out.push('\n');
+    }
+    out
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Step {
+    North,
+    East,
+    South,
+    West,
+}
+
+/// The clockwise boundary of a random polygon that fits in a `rows` by `columns` box, as its top left corner and the
+/// unit steps around from there. Each column of the polygon is a single run of cells that overlaps the column next to
+/// it, which is enough to make sure the boundary never touches itself.
+fn polygon(rng: &mut Rng, rows: usize, columns: usize) -> ((usize, usize), Vec<Step>) {
+    let mut spans = Vec::with_capacity(columns);
+    let mut top = rng.range(0..rows);
+    let mut bottom = rng.range(top + 1..rows + 1);
+    for _ in 0..columns {
+        spans.push((top, bottom));
+        let next_top = (top as i64 + rng.signed(-2..3)).clamp(0, bottom as i64 - 1) as usize;
+        bottom = (bottom as i64 + rng.signed(-2..3)).clamp(top.max(next_top) as i64 + 1, rows as i64) as usize;
+        top = next_top;
+    }
+
+    let vertical = |from: usize, to: usize| {
+        let step = if to < from { Step::North } else { Step::South };
+        std::iter::repeat_n(step, from.abs_diff(to))
+    };
+    let mut steps = Vec::new();
+    for (j, &(top, _)) in spans.iter().enumerate() {
+        if j > 0 {
+            steps.extend(vertical(spans[j - 1].0, top));
+        }
+        steps.push(Step::East);
+    }
+    let (last_top, last_bottom) = spans[columns - 1];
+    steps.extend(vertical(last_top, last_bottom));
+    for j in (0..columns).rev() {
+        steps.push(Step::West);
+        if j > 0 {
+            steps.extend(vertical(spans[j].1, spans[j - 1].1));
+        }
+    }
+    steps.extend(vertical(spans[0].1, spans[0].0));
+    ((spans[0].0, 0), steps)
+}
+
+/// Unit steps merged into runs of the same direction
+fn runs(steps: &[Step]) -> Vec<(Step, usize)> {
+    let mut runs: Vec<(Step, usize)> = Vec::new();
+    for &step in steps {
+        match runs.last_mut() {
+            Some((last, len)) if *last == step => *len += 1,
+            _ => runs.push((step, 1)),
+        }
+    }
+    runs
+}
+
+/// A `size` by `size` field of pipes, with one loop through S and junk pipes everywhere else
+fn day10(rng: &mut Rng, size: usize) -> String {
+    // The loop follows a polygon drawn at double scale, so that there are gaps to squeeze between pipes
+    let cells = (size - 1) / 2;
+    let (corner, steps) = polygon(rng, cells, cells);
+    let steps: Vec<Step> = steps.into_iter().flat_map(|s| [s, s]).collect();
+    let mut tiles: Vec<Vec<char>> = (0..size)
+        .map(|_| (0..size).map(|_| if rng.chance(0.4) { '.' } else { *rng.choose(b"|-LJ7F") as char }).collect())
+        .collect();
+
+    let mut posn = (2 * corner.0, 2 * corner.1);
+    let mut path = Vec::with_capacity(steps.len());
+    for (i, &step) in steps.iter().enumerate() {
+        posn = match step {
+            Step::North => (posn.0 - 1, posn.1),
+            Step::East => (posn.0, posn.1 + 1),
+            Step::South => (posn.0 + 1, posn.1),
+            Step::West => (posn.0, posn.1 - 1),
+        };
+        tiles[posn.0][posn.1] = match (step, steps[(i + 1) % steps.len()]) {
+            (Step::North, Step::North) | (Step::South, Step::South) => '|',
+            (Step::East, Step::East) | (Step::West, Step::West) => '-',
+            (Step::South, Step::East) | (Step::West, Step::North) => 'L',
+            (Step::South, Step::West) | (Step::East, Step::North) => 'J',
+            (Step::East, Step::South) | (Step::North, Step::West) => '7',
+            (Step::North, Step::East) | (Step::West, Step::South) => 'F',
+            _ => unreachable!("The loop never doubles back on itself"),
+        };
+        path.push(posn);
+    }
+
+    // Anything next to S that isn't on the loop is cleared, so that only the loop's two pipes lead into it
+    let k = rng.range(0..path.len());
+    let (start, before, after) = (path[k], path[(k + path.len() - 1) % path.len()], path[(k + 1) % path.len()]);
+    let neighbours = [
+        (start.0.wrapping_sub(1), start.1),
+        (start.0 + 1, start.1),
+        (start.0, start.1.wrapping_sub(1)),
+        (start.0, start.1 + 1),
+    ];
+    for (i, j) in neighbours {
+        if i < size && j < size && (i, j) != before && (i, j) != after {
+            tiles[i][j] = '.';
+        }
+    }
+    tiles[start.0][start.1] = 'S';
+    tiles.into_iter().map(|row| row.into_iter().collect::<String>() + "\n").collect()
+}
+
+/// A `size` by `size` image with a sparse scattering of galaxies
+fn day11(rng: &mut Rng, size: usize) -> String {
+    let mut image = grid(size, size, |_, _| if rng.chance(0.02) { '#' } else { '.' });
+    // Make sure that there is at least one pair of galaxies
+    image.replace_range(0..1, "#");
+    image.replace_range(image.len() - 2..image.len() - 1, "#");
+    image
+}
+
+/// `size` rows of springs, with the damaged groups worked out before some of the springs are hidden
+fn day12(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size {
+        let mut springs: Vec<bool> = (0..rng.range(1..21)).map(|_| rng.chance(0.5)).collect();
+        let at = rng.range(0..springs.len());
+        springs[at] = true;
+        let row: String = springs
+            .iter()
+            .map(|&d| {
+                if rng.chance(0.5) {
+                    '?'
+                } else if d {
+                    '#'
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        let groups: Vec<String> =
+            springs.split(|&d| !d).filter(|g| !g.is_empty()).map(|g| g.len().to_string()).collect();
+        let _ = writeln!(out, "{row} {}", groups.join(","));
+    }
+    out
+}
+
+/// `size` patterns, each with a perfect reflection and a reflection that is one smudge away from perfect
+fn day13(rng: &mut Rng, size: usize) -> String {
+    (0..size).map(|_| mirrored_pattern(rng)).collect::<Vec<_>>().join("\n")
+}
+
+fn mirrored_pattern(rng: &mut Rng) -> String {
+    let (height, width) = (rng.range(5..18), rng.range(5..18));
+    // A perfect reflection between columns, off centre so that some columns aren't reflected at all
+    let column = loop {
+        let column = rng.range(1..width);
+        if 2 * column != width {
+            break column;
+        }
+    };
+    let unreflected: Vec<usize> =
+        if 2 * column < width { (2 * column..width).collect() } else { (0..2 * column - width).collect() };
+    let mut cells: Vec<Vec<bool>> = (0..height)
+        .map(|_| {
+            let mut row: Vec<bool> = (0..width).map(|_| rng.chance(0.5)).collect();
+            for k in 0..column.min(width - column) {
+                row[column + k] = row[column - 1 - k];
+            }
+            row
+        })
+        .collect();
+
+    // A reflection between rows, smudged in a column that the other reflection doesn't care about
+    let row = rng.range(1..height);
+    let reach = row.min(height - row);
+    for k in 0..reach {
+        cells[row + k] = cells[row - 1 - k].clone();
+    }
+    let j = *rng.choose(&unreflected);
+    cells[row + rng.range(0..reach)][j] ^= true;
+
+    let transposed = rng.chance(0.5);
+    let (height, width) = if transposed { (width, height) } else { (height, width) };
+    grid(height, width, |i, j| {
+        let cell = if transposed { cells[j][i] } else { cells[i][j] };
+        if cell {
+            '#'
+        } else {
+            '.'
+        }
+    })
+}
+
+/// A `size` by `size` platform of rounded and cube-shaped rocks
+fn day14(rng: &mut Rng, size: usize) -> String {
+    grid(size, size, |_, _| match rng.range(0..10) {
+        0..2 => 'O',
+        2 => '#',
+        _ => '.',
+    })
+}
+
+/// `size` steps of the initialization sequence, which share a small number of labels between them
+fn day15(rng: &mut Rng, size: usize) -> String {
+    let labels = lowercase_names(rng, size.div_ceil(8), 2, &[]);
+    let steps: Vec<String> = (0..size)
+        .map(|_| {
+            let label = rng.choose(&labels);
+            if rng.chance(0.6) {
+                format!("{label}={}", rng.range(1..10))
+            } else {
+                format!("{label}-")
+            }
+        })
+        .collect();
+    steps.join(",") + "\n"
+}
+
+/// A `size` by `size` contraption of mirrors and splitters
+fn day16(rng: &mut Rng, size: usize) -> String {
+    grid(size, size, |_, _| if rng.chance(0.1) { *rng.choose(b"/\\|-") as char } else { '.' })
+}
+
+/// A `size` by `size` map of heat loss
+fn day17(rng: &mut Rng, size: usize) -> String {
+    grid(size, size, |_, _| char::from(b'0' + rng.range(1..10) as u8))
+}
+
+/// A dig plan around a polygon that fits in a `size` by `size` box. The colours draw the same polygon again, but with
+/// each row and column stretched by a different amount.
+fn day18(rng: &mut Rng, size: usize) -> String {
+    let ((top, left), steps) = polygon(rng, size, size);
+    // Keeping every distance within the five hex digits the colours have for it
+    let most = (0xfffff / size).max(1);
+    let mut stretch = || {
+        let mut offsets = vec![0];
+        for i in 0..size {
+            offsets.push(offsets[i] + rng.range(1..most + 1));
+        }
+        offsets
+    };
+    let (rows, columns) = (stretch(), stretch());
+
+    let (mut i, mut j) = (top, left);
+    let mut out = String::new();
+    for (step, len) in runs(&steps) {
+        let (before, (letter, digit)) = (
+            (rows[i], columns[j]),
+            match step {
+                Step::East => ('R', 0),
+                Step::South => ('D', 1),
+                Step::West => ('L', 2),
+                Step::North => ('U', 3),
+            },
+        );
+        match step {
+            Step::East => j += len,
+            Step::South => i += len,
+            Step::West => j -= len,
+            Step::North => i -= len,
+        }
+        let distance = rows[i].abs_diff(before.0) + columns[j].abs_diff(before.1);
+        let _ = writeln!(out, "{letter} {len} (#{distance:05x}{digit})");
+    }
+    out
+}
+
+/// `size` workflows, which pass parts down a tree from "in" so that none of them loop, and `size` parts
+fn day19(rng: &mut Rng, size: usize) -> String {
+    let mut names = vec!["in".to_string()];
+    names.extend(lowercase_names(rng, size - 1, 2, &["in"]));
+    let mut children: Vec<Vec<&str>> = vec![Vec::new(); size];
+    for (i, name) in names.iter().enumerate().skip(1) {
+        children[rng.range(0..i)].push(name);
+    }
+
+    let mut workflows = Vec::with_capacity(size);
+    for (name, mut targets) in names.iter().zip(children) {
+        for _ in 0..rng.range(1..3).max(2usize.saturating_sub(targets.len())) {
+            targets.push(if rng.chance(0.5) { "A" } else { "R" });
+        }
+        rng.shuffle(&mut targets);
+        let otherwise = targets.pop().unwrap();
+        let rules: Vec<String> = targets
+            .iter()
+            .map(|target| {
+                let (field, operator) = (*rng.choose(b"xmas") as char, *rng.choose(b"<>") as char);
+                format!("{field}{operator}{}:{target}", rng.range(2..4000))
+            })
+            .collect();
+        workflows.push(format!("{name}{{{},{otherwise}}}", rules.join(",")));
+    }
+    rng.shuffle(&mut workflows);
+
+    let parts: Vec<String> = (0..size)
+        .map(|_| {
+            let [x, m, a, s] = [0; 4].map(|_| rng.range(1..4001));
+            format!("{{x={x},m={m},a={a},s={s}}}")
+        })
+        .collect();
+    format!("{}\n\n{}\n", workflows.join("\n"), parts.join("\n"))
+}
+
+/// `size` twelve bit counters built out of flip-flops, each of which sends a low pulse towards rx when it reaches a
+/// different number (with its top and bottom bits set)
+fn day20(rng: &mut Rng, size: usize) -> String {
+    let mut names = lowercase_names(rng, 14 * size + 1, 2, &["rx", "broadcaster"]).into_iter();
+    let mut next_name = || names.next().unwrap();
+    let last = next_name();
+
+    let mut lines = Vec::new();
+    let mut firsts = Vec::new();
+    for _ in 0..size {
+        let bits: Vec<String> = (0..12).map(|_| next_name()).collect();
+        let (hub, inverter) = (next_name(), next_name());
+        let target = rng.range(1 << 11..1 << 12) | 1;
+        // The hub sees the bits that are set in the target, and resets the rest of the counter once they all are
+        let mut from_hub = vec![inverter.clone()];
+        for (i, bit) in bits.iter().enumerate() {
+            let is_set = target >> i & 1 == 1;
+            let mut outputs: Vec<&str> = bits.get(i + 1).map(String::as_str).into_iter().collect();
+            if is_set {
+                outputs.push(&hub);
+            }
+            if !is_set || i == 0 {
+                from_hub.push(bit.clone());
+            }
+            rng.shuffle(&mut outputs);
+            lines.push(format!("%{bit} -> {}", outputs.join(", ")));
+        }
+        rng.shuffle(&mut from_hub);
+        lines.push(format!("&{hub} -> {}", from_hub.join(", ")));
+        lines.push(format!("&{inverter} -> {last}"));
+        firsts.push(bits[0].clone());
+    }
+    lines.push(format!("&{last} -> rx"));
+    lines.push(format!("broadcaster -> {}", firsts.join(", ")));
+    rng.shuffle(&mut lines);
+    lines.join("\n") + "\n"
+}
+
+/// A `size` by `size` garden with S in the middle, and clear paths along the middle and around the edge
+fn day21(rng: &mut Rng, size: usize) -> String {
+    let middle = size / 2;
+    grid(size, size, |i, j| match (i, j) {
+        _ if (i, j) == (middle, middle) => 'S',
+        _ if i == middle || j == middle || i == 0 || j == 0 || i == size - 1 || j == size - 1 => '.',
+        _ if rng.chance(0.15) => '#',
+        _ => '.',
+    })
+}
+
+/// A snapshot of `size` falling bricks over a 10 by 10 area
+fn day22(rng: &mut Rng, size: usize) -> String {
+    let mut occupied = HashSet::new();
+    let mut out = String::new();
+    for _ in 0..size {
+        let len = rng.range(0..4);
+        let (dx, dy, dz) = [(len, 0, 0), (0, len, 0), (0, 0, len)][rng.range(0..3)];
+        let (x, y) = (rng.range(0..10 - dx), rng.range(0..10 - dy));
+        let mut z = rng.range(1..size / 4 + 2);
+        let cubes = |z: usize| (0..=len).map(move |k| (x + k * dx.min(1), y + k * dy.min(1), z + k * dz.min(1)));
+        while cubes(z).any(|cube| occupied.contains(&cube)) {
+            z += 1;
+        }
+        occupied.extend(cubes(z));
+        let _ = writeln!(out, "{x},{y},{z}~{},{},{}", x + dx, y + dy, z + dz);
+    }
+    out
+}
+
+/// A map of hiking trails joining a `size` by `size` lattice of junctions, with slopes leading into and out of
+/// every junction. The top row and right hand column of trails are always there, so that the end can be reached.
+fn day23(rng: &mut Rng, size: usize) -> String {
+    let mut spacing = || {
+        let mut offsets = vec![1];
+        for i in 1..size {
+            offsets.push(offsets[i - 1] + rng.range(4..11));
+        }
+        offsets
+    };
+    let (rows, columns) = (spacing(), spacing());
+    let (height, width) = (rows[size - 1] + 2, columns[size - 1] + 2);
+    let mut tiles = vec![vec!['#'; width]; height];
+    tiles[0][1] = '.';
+    tiles[height - 1][width - 2] = '.';
+
+    for (a, &i) in rows.iter().enumerate() {
+        for (b, &j) in columns.iter().enumerate() {
+            tiles[i][j] = '.';
+            if b + 1 < size && (a == 0 || rng.chance(0.8)) {
+                let end = columns[b + 1];
+                for tile in &mut tiles[i][j + 1..end] {
+                    *tile = '.';
+                }
+                tiles[i][j + 1] = '>';
+                tiles[i][end - 1] = '>';
+            }
+            if a + 1 < size && (b == size - 1 || rng.chance(0.8)) {
+                let end = rows[a + 1];
+                for row in &mut tiles[i + 1..end] {
+                    row[j] = '.';
+                }
+                tiles[i + 1][j] = 'v';
+                tiles[end - 1][j] = 'v';
+            }
+        }
+    }
+    tiles.into_iter().map(|row| row.into_iter().collect::<String>() + "\n").collect()
+}
+
+/// `size` hailstones, all of which a rock thrown from the right place will hit at a different time
+fn day24(rng: &mut Rng, size: usize) -> String {
+    let rock = [0; 3].map(|_| rng.signed(200_000_000_000_000..400_000_000_000_000));
+    let rock_velocity = [0; 3].map(|_| rng.signed(-200..201));
+    let mut times = HashSet::new();
+    let mut out = String::new();
+    for _ in 0..size {
+        let time = loop {
+            let time = rng.signed(1..200_000_000_000);
+            if times.insert(time) {
+                break time;
+            }
+        };
+        let velocity = loop {
+            let velocity = [0; 3].map(|_| rng.signed(-400..401));
+            if velocity[0] != 0 && velocity != rock_velocity {
+                break velocity;
+            }
+        };
+        let [x, y, z] = [0, 1, 2].map(|i| rock[i] + (rock_velocity[i] - velocity[i]) * time);
+        let [vx, vy, vz] = velocity;
+        let _ = writeln!(out, "{x}, {y}, {z} @ {vx}, {vy}, {vz}");
+    }
+    out
+}
+
+/// `size` components wired into two well connected groups, with exactly three wires between the groups
+fn day25(rng: &mut Rng, size: usize) -> String {
+    let names = lowercase_names(rng, size, 3, &[]);
+    let split = rng.range(size / 3..size - size / 3 + 1);
+    let mut wires = HashSet::new();
+    for group in [0..split, split..size] {
+        for v in group.clone() {
+            let mut others: Vec<usize> = group.clone().filter(|&w| w != v).collect();
+            rng.shuffle(&mut others);
+            wires.extend(others[..4].iter().map(|&w| (v.min(w), v.max(w))));
+        }
+    }
+    let mut between = Vec::new();
+    while between.len() < 3 {
+        let (v, w) = (rng.range(0..split), rng.range(split..size));
+        if between.iter().all(|&(a, b)| a != v && b != w) {
+            between.push((v, w));
+        }
+    }
+    wires.extend(between);
+
+    // Each wire is listed by just one of its ends
+    let mut listed: HashMap<usize, Vec<&str>> = HashMap::new();
+    let mut wires: Vec<(usize, usize)> = wires.into_iter().collect();
+    wires.sort_unstable();
+    for (v, w) in wires {
+        let (from, to) = if rng.chance(0.5) { (v, w) } else { (w, v) };
+        listed.entry(from).or_default().push(&names[to]);
+    }
+    let mut lines: Vec<String> = listed.into_iter().map(|(v, to)| format!("{}: {}", names[v], to.join(" "))).collect();
+    lines.sort_unstable();
+    rng.shuffle(&mut lines);
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{self, Unimplemented};
+
+    #[test]
+    fn test_same_seed_same_input() {
+        for day in 1..=25 {
+            assert_eq!(input(day, 7, 20).unwrap(), input(day, 7, 20).unwrap(), "day {day}");
+        }
+        assert_ne!(input(5, 1, 20).unwrap(), input(5, 2, 20).unwrap());
+    }
+
+    #[test]
+    fn test_inputs_are_valid() {
+        for solution in solution::registry() {
+            let day = solution.day();
+            for seed in 0..4 {
+                let input = input(day, seed, (default_size(day) / 10).max(3)).unwrap();
+                for part in [1, 2] {
+                    match solution::solve(solution.as_ref(), part, &input) {
+                        Err(e) if !e.is::<Unimplemented>() => panic!("{day:02}-{part} with seed {seed}: {e:#}"),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use anyhow::{anyhow, bail, Error, Result};
+use std::{
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use crate::{
+    parse::Locate,
+    point::{Direction, Point},
+};
+
+/// A (row, column) index into a grid
+pub type Posn = (usize, usize);
+
+/// A rectangular 2D grid of tiles, stored row by row
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        let mut cells = Vec::with_capacity(width * height);
+        for (i, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                bail!("Row {} has {} tiles but row 1 has {width}", i + 1, row.len());
+            }
+            cells.extend(row);
+        }
+        Ok(Self { width, height, cells })
+    }
+
+    /// Parses one tile per character, one row per line, using `parse_tile` rather than `TryFrom<char>`
+    pub fn parse_with<E: Into<Error>>(input: &str, mut parse_tile: impl FnMut(char) -> Result<T, E>) -> Result<Self> {
+        let mut rows: Vec<Vec<T>> = Vec::new();
+        for line in input.lines() {
+            let mut row: Vec<T> = Vec::new();
+            for (j, c) in line.char_indices() {
+                row.push(parse_tile(c).at(&line[j..j + c.len_utf8()])?);
+            }
+            let width = rows.first().map_or(row.len(), Vec::len);
+            if row.len() != width {
+                return Err(anyhow!("Row {} has {} tiles but row 1 has {width}", rows.len() + 1, row.len())).at(line);
+            }
+            rows.push(row);
+        }
+        Self::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (i, j): Posn) -> bool {
+        i < self.height && j < self.width
+    }
+
+    pub fn get(&self, posn: Posn) -> Option<&T> {
+        self.contains(posn).then(|| &self.cells[posn.0 * self.width + posn.1])
+    }
+
+    pub fn get_mut(&mut self, posn: Posn) -> Option<&mut T> {
+        self.contains(posn).then(|| &mut self.cells[posn.0 * self.width + posn.1])
+    }
+
+    /// Every position in the grid, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Posn> + use<T> {
+        let width = self.width;
+        (0..self.height).flat_map(move |i| (0..width).map(move |j| (i, j)))
+    }
+
+    /// Every tile in the grid alongside its position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Posn, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// The position of the first tile (row by row) matching `predicate`
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Posn> {
+        self.iter().find(|(_, tile)| predicate(tile)).map(|(posn, _)| posn)
+    }
+
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.cells[i * self.width..(i + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on a chunk size of 0, and an empty grid has no rows anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, j: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(j < self.width, "column {j} is out of bounds for a grid of width {}", self.width);
+        self.cells.iter().skip(j).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|j| self.column(j))
+    }
+
+    /// The position one step from `posn` in `direction`, or None if that would leave the grid
+    pub fn step(&self, posn: Posn, direction: Direction) -> Option<Posn> {
+        (Point::from(posn) + direction).to_posn().filter(|&next| self.contains(next))
+    }
+
+    /// The in-bounds positions directly above, below, left of, and right of `posn`
+    pub fn neighbours4(&self, (i, j): Posn) -> impl Iterator<Item = Posn> + use<'_, T> {
+        [(i.wrapping_sub(1), j), (i + 1, j), (i, j.wrapping_sub(1)), (i, j + 1)]
+            .into_iter()
+            .filter(|&posn| self.contains(posn))
+    }
+
+    /// The in-bounds positions surrounding `posn`, including diagonals
+    pub fn neighbours8(&self, (i, j): Posn) -> impl Iterator<Item = Posn> + use<'_, T> {
+        [i.wrapping_sub(1), i, i + 1]
+            .into_iter()
+            .flat_map(move |a| [j.wrapping_sub(1), j, j + 1].map(|b| (a, b)))
+            .filter(move |&posn| posn != (i, j) && self.contains(posn))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, tile: T) -> Self {
+        Self { width, height, cells: vec![tile; width * height] }
+    }
+
+    /// Swaps rows and columns, so that the top row becomes the leftmost column
+    pub fn transpose(&self) -> Self {
+        let cells = (0..self.width).flat_map(|j| self.column(j).cloned()).collect();
+        Self { width: self.height, height: self.width, cells }
+    }
+
+    /// Rotates a quarter turn clockwise, so that the leftmost column becomes the top row
+    pub fn rotate_clockwise(&self) -> Self {
+        let cells = (0..self.width).flat_map(|j| self.column(j).rev().cloned()).collect();
+        Self { width: self.height, height: self.width, cells }
+    }
+
+    /// Rotates a quarter turn anticlockwise, so that the rightmost column becomes the top row
+    pub fn rotate_anticlockwise(&self) -> Self {
+        let cells = (0..self.width).rev().flat_map(|j| self.column(j).cloned()).collect();
+        Self { width: self.height, height: self.width, cells }
+    }
+}
+
+impl<T> Default for Grid<T> {
+    fn default() -> Self {
+        Self { width: 0, height: 0, cells: Vec::new() }
+    }
+}
+
+impl<T> Index<Posn> for Grid<T> {
+    type Output = T;
+    fn index(&self, posn: Posn) -> &T {
+        let (width, height) = (self.width, self.height);
+        self.get(posn).unwrap_or_else(|| panic!("{posn:?} is out of bounds for a {height}x{width} grid"))
+    }
+}
+
+impl<T> IndexMut<Posn> for Grid<T> {
+    fn index_mut(&mut self, posn: Posn) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(posn).unwrap_or_else(|| panic!("{posn:?} is out of bounds for a {height}x{width} grid"))
+    }
+}
+
+impl<T> FromStr for Grid<T>
+where
+    T: TryFrom<char>,
+    T::Error: Into<Error>,
+{
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Self> {
+        Self::parse_with(input, T::try_from)
+    }
+}
+
+impl<T: Copy> Display for Grid<T>
+where
+    char: From<T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            let line: String = row.iter().map(|&tile| char::from(tile)).collect();
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "abc
+def
+";
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid: Grid<char> = EXAMPLE.parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 0)], 'd');
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.to_string(), EXAMPLE);
+        assert!("ab\nc\n".parse::<Grid<char>>().is_err());
+
+        let input = "..\n.x\n";
+        let error = Grid::parse_with(input, |c| if c == '.' { Ok(()) } else { Err(anyhow::anyhow!("bad")) });
+        let diagnostic = crate::parse::diagnose(error.unwrap_err(), input).to_string();
+        assert_eq!(diagnostic.lines().nth(1), Some(" --> line 2, column 2"));
+        let ragged = "ab\nc\n";
+        let diagnostic = crate::parse::diagnose(ragged.parse::<Grid<char>>().unwrap_err(), ragged).to_string();
+        assert_eq!(diagnostic.lines().nth(1), Some(" --> line 2, column 1"));
+    }
+
+    #[test]
+    fn test_views() {
+        let grid: Grid<char> = EXAMPLE.parse().unwrap();
+        assert_eq!(grid.row(1), ['d', 'e', 'f']);
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(grid.column(2).collect::<String>(), "cf");
+        assert_eq!(grid.columns().map(|c| c.collect::<String>()).collect::<Vec<_>>(), ["ad", "be", "cf"]);
+        assert_eq!(grid.position(|&c| c == 'e'), Some((1, 1)));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid: Grid<char> = EXAMPLE.parse().unwrap();
+        assert_eq!(grid.neighbours4((0, 0)).collect::<Vec<_>>(), [(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours4((1, 1)).collect::<Vec<_>>(), [(0, 1), (1, 0), (1, 2)]);
+        assert_eq!(grid.neighbours8((0, 0)).collect::<Vec<_>>(), [(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 5);
+        assert_eq!(grid.step((0, 0), Direction::North), None);
+        assert_eq!(grid.step((0, 0), Direction::South), Some((1, 0)));
+    }
+
+    #[test]
+    fn test_transformations() {
+        let grid: Grid<char> = EXAMPLE.parse().unwrap();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_anticlockwise().to_string(), "cf\nbe\nad\n");
+        assert_eq!(grid.rotate_clockwise().rotate_anticlockwise(), grid);
+        assert_eq!(grid.transpose().transpose(), grid);
+    }
+}
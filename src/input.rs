@@ -0,0 +1,83 @@
+use anyhow::{Context, Result};
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+/// Where to read a day's puzzle input from
+#[derive(Debug, PartialEq)]
+pub enum Source {
+    Stdin,
+    File(PathBuf),
+    /// A directory of `dayNN.txt` files
+    Directory(PathBuf),
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Self::Directory(PathBuf::from("inputs"))
+    }
+}
+
+impl From<&str> for Source {
+    /// `-` means stdin, anything else is a path to a single input file
+    fn from(value: &str) -> Self {
+        match value {
+            "-" => Self::Stdin,
+            path => Self::File(PathBuf::from(path)),
+        }
+    }
+}
+
+impl Source {
+    pub fn path(&self, day: u8) -> Option<PathBuf> {
+        match self {
+            Self::Stdin => None,
+            Self::File(path) => Some(path.clone()),
+            Self::Directory(dir) => Some(dir.join(format!("day{day:02}.txt"))),
+        }
+    }
+
+    pub fn read(&self, day: u8) -> Result<String> {
+        match self.path(day) {
+            Some(path) => read_file(day, &path),
+            None => {
+                let mut input = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .with_context(|| format!("Could not read the input for day {day:02} from stdin"))?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+fn read_file(day: u8, path: &Path) -> Result<String> {
+    std::fs::read_to_string(path)
+        .with_context(|| format!("Could not read the input for day {day:02} from {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_from_str() {
+        assert_eq!(Source::from("-"), Source::Stdin);
+        assert_eq!(Source::from("my/input.txt"), Source::File(PathBuf::from("my/input.txt")));
+    }
+
+    #[test]
+    fn test_path() {
+        assert_eq!(Source::default().path(5), Some(PathBuf::from("inputs/day05.txt")));
+        assert_eq!(Source::Directory(PathBuf::from("elsewhere")).path(25), Some(PathBuf::from("elsewhere/day25.txt")));
+        assert_eq!(Source::Stdin.path(1), None);
+    }
+
+    #[test]
+    fn test_read() {
+        assert!(Source::default().read(1).unwrap().starts_with("eighttkbtzjz6nineeight"));
+        let error = Source::Directory(PathBuf::from("no-such-dir")).read(7).unwrap_err();
+        assert_eq!(format!("{error}"), "Could not read the input for day 07 from no-such-dir/day07.txt");
+    }
+}
@@ -0,0 +1,189 @@
+use std::ops::Range;
+
+/// A set of integers, stored as sorted, disjoint, non-adjacent half-open ranges
+#[derive(Clone, Default, PartialEq, Eq, Hash, Debug)]
+pub struct IntervalSet {
+    ranges: Vec<Range<usize>>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ranges(&self) -> &[Range<usize>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The number of values in the set
+    pub fn len(&self) -> usize {
+        self.ranges.iter().map(|r| r.len()).sum()
+    }
+
+    pub fn min(&self) -> Option<usize> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    pub fn max(&self) -> Option<usize> {
+        self.ranges.last().map(|r| r.end - 1)
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        // The first range that ends after the value is the only one that could contain it
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).is_some_and(|r| r.contains(&value))
+    }
+
+    pub fn insert(&mut self, range: Range<usize>) {
+        *self = self.union(&range.into());
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        self.ranges.iter().chain(other.ranges.iter()).cloned().collect()
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let overlap = a.start.max(b.start)..a.end.min(b.end);
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+            // Whichever range ends first can't overlap anything else in the other set
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    /// The values in this set that are not in `other`
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        let mut others = other.ranges.iter().peekable();
+        for range in &self.ranges {
+            let mut start = range.start;
+            while let Some(hole) = others.peek() {
+                if hole.end <= start {
+                    others.next();
+                } else if hole.start >= range.end {
+                    break;
+                } else {
+                    if hole.start > start {
+                        ranges.push(start..hole.start);
+                    }
+                    start = hole.end;
+                    if hole.end > range.end {
+                        break; // This hole may cut into the next range too
+                    }
+                    others.next();
+                }
+            }
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Splits into the values less than `threshold` and the values at least `threshold`
+    pub fn split_at(&self, threshold: usize) -> (IntervalSet, IntervalSet) {
+        let below = self.intersection(&(0..threshold).into());
+        let above = self.difference(&below);
+        (below, above)
+    }
+
+    /// Maps the values in each piece's source range onto the range of the same length starting at its destination.
+    /// Where pieces overlap the first one applies, and values not in any piece map to themselves.
+    pub fn remap(&self, pieces: impl IntoIterator<Item = (Range<usize>, usize)>) -> IntervalSet {
+        let mut remaining = self.clone();
+        let mut mapped = IntervalSet::new();
+        for (source, destination) in pieces {
+            let source = IntervalSet::from(source);
+            for r in remaining.intersection(&source).ranges {
+                let offset = source.ranges[0].start;
+                mapped.insert(r.start - offset + destination..r.end - offset + destination);
+            }
+            remaining = remaining.difference(&source);
+        }
+        mapped.union(&remaining)
+    }
+}
+
+impl From<Range<usize>> for IntervalSet {
+    fn from(range: Range<usize>) -> Self {
+        Self { ranges: if range.is_empty() { Vec::new() } else { vec![range] } }
+    }
+}
+
+impl FromIterator<Range<usize>> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = Range<usize>>>(iter: T) -> Self {
+        let mut unsorted: Vec<Range<usize>> = iter.into_iter().filter(|r| !r.is_empty()).collect();
+        unsorted.sort_unstable_by_key(|r| r.start);
+
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for range in unsorted {
+            match ranges.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => ranges.push(range),
+            }
+        }
+        Self { ranges }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[Range<usize>]) -> IntervalSet {
+        ranges.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_normalisation() {
+        assert_eq!(set(&[5..8, 0..2, 1..3, 3..4, 6..7, 9..9]).ranges(), [0..4, 5..8]);
+        let mut s = set(&[0..2, 5..8]);
+        s.insert(2..5);
+        assert_eq!(s, IntervalSet::from(0..8));
+        assert_eq!(s.len(), 8);
+        assert_eq!((s.min(), s.max()), (Some(0), Some(7)));
+        assert!(s.contains(7) && !s.contains(8));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[0..10, 20..30]);
+        let b = set(&[5..25, 28..40]);
+        assert_eq!(a.union(&b), IntervalSet::from(0..40));
+        assert_eq!(a.intersection(&b).ranges(), [5..10, 20..25, 28..30]);
+        assert_eq!(a.difference(&b).ranges(), [0..5, 25..28]);
+        assert_eq!(b.difference(&a).ranges(), [10..20, 30..40]);
+        assert_eq!(a.difference(&set(&[2..3, 4..5, 8..22])).ranges(), [0..2, 3..4, 5..8, 22..30]);
+        assert!(a.difference(&a).is_empty());
+    }
+
+    #[test]
+    fn test_split_at() {
+        let (below, above) = set(&[0..10, 20..30]).split_at(25);
+        assert_eq!(below.ranges(), [0..10, 20..25]);
+        assert_eq!(above, IntervalSet::from(25..30));
+    }
+
+    #[test]
+    fn test_remap() {
+        let s = IntervalSet::from(0..100);
+        let remapped = s.remap([(10..20, 50), (15..30, 0), (50..60, 200)]);
+        // 10..20 -> 50..60, 20..30 -> 5..15, 50..60 -> 200..210, and everything else stays put
+        assert_eq!(remapped.ranges(), [0..15, 30..100, 200..210]);
+        // 5..10 is both mapped onto and left in place, so there are fewer values than before
+        assert_eq!(remapped.len(), 95);
+    }
+}
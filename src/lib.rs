@@ -0,0 +1,48 @@
+//! Solutions to Advent of Code 2023.
+//!
+//! Each `dayNN` module exposes `part1` and `part2` functions that take the raw puzzle input,
+//! along with the domain types that the input is parsed into (all of which implement `FromStr`).
+//! [`solution::registry`] lists every day behind the uniform [`solution::Solution`] trait.
+
+pub mod answers;
+pub mod bench;
+pub mod cycle;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod examples;
+pub mod generate;
+pub mod grid;
+pub mod input;
+pub mod interval;
+pub mod parallel;
+pub mod parse;
+pub mod point;
+pub mod render;
+pub mod report;
+pub mod solution;
+pub mod state;
+pub mod submit;
+pub mod visualize;
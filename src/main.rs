@@ -1,119 +1,431 @@
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
-
-fn main() {
-    let input = std::fs::read_to_string("inputs/day01.txt").unwrap();
-    println!("01-1: {}", day01::part1(&input));
-    println!("01-2: {}", day01::part2(&input));
-
-    let input = std::fs::read_to_string("inputs/day02.txt").unwrap();
-    println!("02-1: {}", day02::part1(&input).unwrap());
-    println!("02-2: {}", day02::part2(&input).unwrap());
-
-    let input = std::fs::read_to_string("inputs/day03.txt").unwrap();
-    println!("03-1: {}", day03::part1(&input));
-    println!("03-2: {}", day03::part2(&input));
-
-    let input = std::fs::read_to_string("inputs/day04.txt").unwrap();
-    println!("04-1: {}", day04::part1(&input));
-    println!("04-2: {}", day04::part2(&input));
-
-    let input = std::fs::read_to_string("inputs/day05.txt").unwrap();
-    println!("05-1: {}", day05::part1(&input).unwrap());
-    println!("05-2: {}", day05::part2(&input).unwrap());
-
-    let input = std::fs::read_to_string("inputs/day06.txt").unwrap();
-    println!("06-1: {}", day06::part1(&input).unwrap());
-    println!("06-2: {}", day06::part2(&input).unwrap());
-
-    let input = std::fs::read_to_string("inputs/day07.txt").unwrap();
-    println!("07-1: {}", day07::part1(&input).unwrap());
-    println!("07-2: {}", day07::part2(&input).unwrap());
-
-    let input = std::fs::read_to_string("inputs/day08.txt").unwrap();
-    println!("08-1: {}", day08::part1(&input).unwrap());
-    println!("08-2: {}", day08::part2(&input).unwrap());
-
-    let input = std::fs::read_to_string("inputs/day09.txt").unwrap();
-    println!("09-1: {}", day09::part1(&input));
-    println!("09-2: {}", day09::part2(&input));
-
-    let input = std::fs::read_to_string("inputs/day10.txt").unwrap();
-    println!("10-1: {}", day10::part1(&input).unwrap());
-
-    let input = std::fs::read_to_string("inputs/day11.txt").unwrap();
-    println!("11-1: {}", day11::part1(&input));
-    println!("11-2: {}", day11::part2(&input));
-
-    let input = std::fs::read_to_string("inputs/day12.txt").unwrap();
-    // println!("12-1: {}", day12::part1(&input).unwrap());
-
-    let input = std::fs::read_to_string("inputs/day13.txt").unwrap();
-    println!("13-1: {}", day13::part1(&input).unwrap());
-
-    let input = std::fs::read_to_string("inputs/day14.txt").unwrap();
-    println!("14-1: {}", day14::part1(&input).unwrap());
-    println!("14-2: {}", day14::part2(&input).unwrap());
-
-    let input = std::fs::read_to_string("inputs/day15.txt").unwrap();
-    println!("15-1: {}", day15::part1(&input));
-    println!("15-2: {}", day15::part2(&input).unwrap());
-
-    let input = std::fs::read_to_string("inputs/day16.txt").unwrap();
-    println!("16-1: {}", day16::part1(&input).unwrap());
-    println!("16-2: {}", day16::part2(&input).unwrap());
-
-    let input = std::fs::read_to_string("inputs/day17.txt").unwrap();
-    println!("17-1: {}", day17::part1(&input).unwrap());
-    println!("17-2: {}", day17::part2(&input).unwrap());
-
-    let input = std::fs::read_to_string("inputs/day18.txt").unwrap();
-    println!("18-1: {}", day18::part1(&input).unwrap());
-
-    let input = std::fs::read_to_string("inputs/day19.txt").unwrap();
-    println!("19-1: {}", day19::part1(&input).unwrap());
-    println!("19-2: {}", day19::part2(&input).unwrap());
-
-    let input = std::fs::read_to_string("inputs/day20.txt").unwrap();
-    println!("20-1: {}", day20::part1(&input).unwrap());
-
-    let input = std::fs::read_to_string("inputs/day21.txt").unwrap();
-    println!("21-1: {}", day21::part1(&input, 64).unwrap());
-    // println!("21-2: {}", day21::part2(&input, 26501365).unwrap());
-
-    let input = std::fs::read_to_string("inputs/day22.txt").unwrap();
-    println!("22-1: {}", day22::part1(&input).unwrap());
-    println!("22-2: {}", day22::part2(&input).unwrap());
-
-    let input = std::fs::read_to_string("inputs/day23.txt").unwrap();
-    println!("23-1: {}", day23::part1(&input).unwrap());
-
-    let input = std::fs::read_to_string("inputs/day24.txt").unwrap();
-    println!("24-1: {}", day24::part1(&input, 2e14..=4e14).unwrap());
+use anyhow::{anyhow, bail, Context, Result};
+use aoc_2023::{
+    answers::{Answers, Verdict},
+    bench, generate,
+    input::Source,
+    parallel, parse, render,
+    report::Record,
+    solution::{self, Unimplemented},
+    state::{self, State},
+    submit::{self, Feedback},
+    visualize,
+};
+use std::{
+    panic,
+    path::PathBuf,
+    process::ExitCode,
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
-    let input = std::fs::read_to_string("inputs/day25.txt").unwrap();
-    println!("25-1: {}", day25::part1(&input).unwrap());
+const USAGE: &str = "Usage: aoc-2023 [run|bench|verify|status|submit|visualize|generate] [--day N] [--part P]
+                 [--input PATH|-] [--inputs-dir DIR] [--runs N] [--answers PATH] [--state PATH] [--format text|json]
+                 [--jobs N] [--answer A] [--render PATH] [--fps N] [--seed N] [--size N]
+
+Commands:
+    run             Run the selected solutions (the default)
+    bench           Time the selected solutions and print a table of parse and total times
+    verify          Check the selected solutions against the known answers
+    status          List whether each part is implemented, with the answer, time and verdict from its last run
+    submit          Check an answer to the selected part against the known answers as the website would, saying
+                    whether it is too high or too low, and wait a minute or more after each wrong answer before
+                    taking another (requires --day and --part)
+    visualize       Replay the selected day's simulation in the terminal (requires --day; days 14, 16, 20 and 22).
+                    Space pauses and resumes, n steps one frame while paused, + and - change the speed, and q quits
+    generate        Print a random input for the selected day (requires --day), for example to pipe into --input -
+
+Options:
+    --day N         Only run day N (1-25)
+    --part P        Only run part P (1 or 2)
+    --input PATH    Read the puzzle input from PATH, or from stdin if PATH is - (requires --day)
+    --inputs-dir D  Read each day's puzzle input from D/dayNN.txt (default inputs)
+    --runs N        Number of times to run each part when benchmarking (default 10)
+    --answers PATH  The file of known answers to verify against (default answers.toml)
+    --state PATH    Where run and verify remember each part's last answer when solving from an inputs directory,
+                    for status to show, and submit its wrong answers (default state.toml)
+    --format F      Print results as text (the default) or as one JSON record per line (run and verify only)
+    --jobs N        Solve up to N parts at once, or one per CPU if N is 0 (run and verify only, default 1)
+    --answer A      The answer to submit (default the selected part's answer to its puzzle input)
+    --render PATH   Draw the selected day's grid (part 1 unless --part is given) to a .png, .ppm or .svg file
+                    instead of solving it (requires --day)
+    --fps N         Frames per second to start visualizing at (default 10)
+    --seed N        The seed to generate an input from (default 0)
+    --size N        How big an input to generate, in whatever the day counts (default roughly the puzzle's size)
+    -h, --help      Print this message";
+
+#[derive(Default, PartialEq, Clone, Copy)]
+enum Command {
+    #[default]
+    Run,
+    Bench,
+    Verify,
+    Status,
+    Submit,
+    Visualize,
+    Generate,
+}
+
+#[derive(Default, PartialEq, Clone, Copy)]
+enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+struct Args {
+    command: Command,
+    day: Option<u8>,
+    part: Option<u8>,
+    source: Source,
+    runs: usize,
+    answers: String,
+    state: String,
+    format: Format,
+    jobs: Option<usize>,
+    answer: Option<String>,
+    render: Option<PathBuf>,
+    fps: f64,
+    seed: u64,
+    size: Option<usize>,
+    help: bool,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            command: Command::default(),
+            day: None,
+            part: None,
+            source: Source::default(),
+            runs: 10,
+            answers: "answers.toml".to_string(),
+            state: "state.toml".to_string(),
+            format: Format::default(),
+            jobs: None,
+            answer: None,
+            render: None,
+            fps: 10.0,
+            seed: 0,
+            size: None,
+            help: false,
+        }
+    }
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Self::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "run" => parsed.command = Command::Run,
+                "bench" => parsed.command = Command::Bench,
+                "verify" => parsed.command = Command::Verify,
+                "status" => parsed.command = Command::Status,
+                "submit" => parsed.command = Command::Submit,
+                "visualize" => parsed.command = Command::Visualize,
+                "generate" => parsed.command = Command::Generate,
+                "-h" | "--help" => parsed.help = true,
+                "--day" => {
+                    let day: u8 = args.next().context("--day requires a value")?.parse()?;
+                    if !(1..=25).contains(&day) {
+                        bail!("--day must be between 1 and 25, got {day}");
+                    }
+                    parsed.day = Some(day);
+                }
+                "--part" => {
+                    let part: u8 = args.next().context("--part requires a value")?.parse()?;
+                    if !(1..=2).contains(&part) {
+                        bail!("--part must be 1 or 2, got {part}");
+                    }
+                    parsed.part = Some(part);
+                }
+                "--input" => parsed.source = args.next().context("--input requires a value")?.as_str().into(),
+                "--inputs-dir" => {
+                    parsed.source = Source::Directory(args.next().context("--inputs-dir requires a value")?.into())
+                }
+                "--answers" => parsed.answers = args.next().context("--answers requires a value")?,
+                "--state" => parsed.state = args.next().context("--state requires a value")?,
+                "--format" => {
+                    parsed.format = match args.next().context("--format requires a value")?.as_str() {
+                        "text" => Format::Text,
+                        "json" => Format::Json,
+                        f => bail!("--format must be text or json, got {f}"),
+                    }
+                }
+                "--jobs" => parsed.jobs = Some(args.next().context("--jobs requires a value")?.parse()?),
+                "--render" => parsed.render = Some(args.next().context("--render requires a value")?.into()),
+                "--fps" => {
+                    parsed.fps = args.next().context("--fps requires a value")?.parse()?;
+                    if parsed.fps.is_nan() || parsed.fps <= 0.0 {
+                        bail!("--fps must be positive, got {}", parsed.fps);
+                    }
+                }
+                "--seed" => parsed.seed = args.next().context("--seed requires a value")?.parse()?,
+                "--size" => parsed.size = Some(args.next().context("--size requires a value")?.parse()?),
+                "--answer" => parsed.answer = Some(args.next().context("--answer requires a value")?),
+                "--runs" => parsed.runs = args.next().context("--runs requires a value")?.parse()?,
+                _ => bail!("Unexpected argument: {arg}"),
+            }
+        }
+        if parsed.command == Command::Bench && parsed.format == Format::Json {
+            bail!("--format json is not supported by bench");
+        }
+        if parsed.command == Command::Bench && parsed.jobs.is_some() {
+            bail!("--jobs is not supported by bench, as parts solved at the same time would skew each other's timings");
+        }
+        if parsed.command == Command::Visualize && parsed.day.is_none() {
+            bail!("visualize can only be used together with --day");
+        }
+        if parsed.command == Command::Submit && (parsed.day.is_none() || parsed.part.is_none()) {
+            bail!("submit can only be used together with --day and --part");
+        }
+        if parsed.answer.is_some() && parsed.command != Command::Submit {
+            bail!("--answer can only be used with submit");
+        }
+        if parsed.command == Command::Generate && parsed.day.is_none() {
+            bail!("generate can only be used together with --day");
+        }
+        if parsed.render.is_some() && parsed.day.is_none() {
+            bail!("--render can only be used together with --day");
+        }
+        if !matches!(parsed.source, Source::Directory(_)) && parsed.day.is_none() {
+            bail!("--input can only be used together with --day");
+        }
+        Ok(parsed)
+    }
+
+    fn selects(&self, day: u8, part: u8) -> bool {
+        self.day.is_none_or(|d| d == day) && self.part.is_none_or(|p| p == part)
+    }
+}
+
+/// The CPU time the current thread has used so far, where the OS reports it (Linux only)
+fn thread_cpu_time() -> Option<Duration> {
+    // Yielding makes the kernel bring the thread's running total up to date
+    thread::yield_now();
+    let schedstat = std::fs::read_to_string("/proc/thread-self/schedstat").ok()?;
+    Some(Duration::from_nanos(schedstat.split_whitespace().next()?.parse().ok()?))
+}
+
+/// Submits the given answer, or else the part's answer to its puzzle input, remembering wrong answers in the state
+fn submit(args: &Args, day: u8, part: u8) -> Result<(String, Feedback)> {
+    let answer = match &args.answer {
+        Some(answer) => answer.clone(),
+        None => {
+            let input = args.source.read(day)?;
+            solution::solve(solution::registry()[day as usize - 1].as_ref(), part, &input)?.to_string()
+        }
+    };
+    let answers = Answers::load(&args.answers).unwrap_or_default();
+    let mut state = State::load(&args.state).unwrap_or_default();
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let feedback = submit::submit(state.get_mut(day, part), answers.get(day, part), &answer, now)?;
+    state.save(&args.state)?;
+    Ok((answer, feedback))
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if args.help {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+    if let (Some(day), Some(path)) = (args.day, &args.render) {
+        let solution = &solution::registry()[day as usize - 1];
+        let part = args.part.unwrap_or(1);
+        let rendered = args
+            .source
+            .read(day)
+            .and_then(|input| solution.render(part, &input).map_err(|e| parse::diagnose(e, &input)));
+        return match rendered.and_then(|image| render::save(&image, path)) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("error: {e:#}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if let (Command::Generate, Some(day)) = (args.command, args.day) {
+        let size = args.size.unwrap_or_else(|| generate::default_size(day));
+        return match generate::input(day, args.seed, size) {
+            Ok(input) => {
+                print!("{input}");
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("error: {e:#}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if let (Command::Submit, Some(day), Some(part)) = (args.command, args.day, args.part) {
+        return match submit(&args, day, part) {
+            Ok((answer, feedback)) => {
+                println!("{day:02}-{part}: {answer}: {feedback}");
+                if feedback.is_wrong() {
+                    ExitCode::FAILURE
+                } else {
+                    ExitCode::SUCCESS
+                }
+            }
+            Err(e) => {
+                eprintln!("error: {e:#}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if args.command == Command::Status {
+        // Probing a part that hits a `todo!()` would otherwise print a panic message
+        panic::set_hook(Box::new(|_| {}));
+        let state = State::load(&args.state).unwrap_or_default();
+        state::print_status_header();
+        for solution in solution::registry() {
+            for part in [1, 2].into_iter().filter(|&p| args.selects(solution.day(), p)) {
+                let implemented = solution::is_implemented(solution.as_ref(), part);
+                println!("{}", state::status_row(solution.day(), part, implemented, state.get(solution.day(), part)));
+            }
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    if let (Command::Visualize, Some(day)) = (args.command, args.day) {
+        let solution = solution::registry().swap_remove(day as usize - 1);
+        let part = args.part.unwrap_or(1);
+        let visualized = args
+            .source
+            .read(day)
+            .and_then(|input| visualize::run(move |on_frame| solution.visualize(part, &input, on_frame), args.fps));
+        return match visualized {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("error: {e:#}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    let answers = match args.command {
+        Command::Visualize | Command::Generate | Command::Submit => {
+            unreachable!("visualize, generate and submit always have a day")
+        }
+        Command::Status => unreachable!("status has already been printed"),
+        Command::Run => Answers::load(&args.answers).unwrap_or_default(),
+        Command::Bench => {
+            bench::print_header();
+            Answers::default()
+        }
+        Command::Verify => match Answers::load(&args.answers) {
+            Ok(answers) => answers,
+            Err(e) => {
+                eprintln!("error: {e:#}");
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+
+    let mut failures = 0;
+    let mut tasks = Vec::new();
+    let registry = solution::registry();
+    let inputs: Vec<_> = registry
+        .iter()
+        .filter(|solution| [1, 2].into_iter().any(|p| args.selects(solution.day(), p)))
+        .map(|solution| (solution.as_ref(), args.source.read(solution.day())))
+        .collect();
+    for (solution, input) in &inputs {
+        let day = solution.day();
+        for part in [1, 2].into_iter().filter(|&p| args.selects(day, p)) {
+            let input = input.as_ref().map_err(|e| anyhow!("{e:#}"));
+
+            if args.command == Command::Bench {
+                match input.and_then(|input| bench::time_part(*solution, part, input, args.runs)) {
+                    Ok(timings) => bench::print_row(day, part, &timings),
+                    Err(e) if e.is::<Unimplemented>() => eprintln!("{day:02}-{part}: {e}"),
+                    Err(e) => {
+                        eprintln!("{day:02}-{part}: error: {e:#}");
+                        failures += 1;
+                    }
+                }
+                continue;
+            }
+            tasks.push((*solution, part, input));
+        }
+    }
+
+    let jobs = match args.jobs {
+        Some(0) => thread::available_parallelism().map_or(1, |n| n.get()),
+        Some(n) => n,
+        None => 1,
+    };
+    // Answers to inputs other than the puzzle inputs are not worth remembering
+    let mut state = match (args.command, &args.source) {
+        (Command::Run | Command::Verify, Source::Directory(_)) => Some(State::load(&args.state).unwrap_or_default()),
+        _ => None,
+    };
+    let start = Instant::now();
+    let mut cpu_time = Some(Duration::ZERO);
+    parallel::map_in_order(
+        tasks,
+        jobs,
+        |(solution, part, input)| {
+            let (start, cpu_start) = (Instant::now(), thread_cpu_time());
+            let result = input.and_then(|input| solution::solve(solution, part, input));
+            let cpu = cpu_start.zip(thread_cpu_time()).map(|(before, after)| after.saturating_sub(before));
+            (solution.day(), part, start.elapsed(), cpu, result)
+        },
+        |(day, part, elapsed, cpu, result)| {
+            cpu_time = cpu_time.zip(cpu).map(|(total, cpu)| total + cpu);
+            let verdict = Verdict::new(answers.get(day, part), result);
+            if let Some(state) = &mut state {
+                state.record(day, part, elapsed, &verdict);
+            }
+            let failed = match args.command {
+                Command::Verify => verdict.is_failure(),
+                _ => matches!(verdict, Verdict::Error(_)),
+            };
+            if failed {
+                failures += 1;
+            }
+
+            match (args.format, args.command) {
+                (Format::Json, _) => println!("{}", Record { day, part, elapsed, verdict }.to_json()),
+                (Format::Text, Command::Verify) => println!("{day:02}-{part}: {verdict}"),
+                (Format::Text, _) => match verdict {
+                    Verdict::Unsolved => eprintln!("{day:02}-{part}: not implemented"),
+                    Verdict::Error(e) => eprintln!("{day:02}-{part}: error: {e}"),
+                    _ => println!("{day:02}-{part}: {}", verdict.answer().unwrap_or_default()),
+                },
+            }
+        },
+    );
+    if matches!(args.command, Command::Run | Command::Verify) {
+        // On stderr so that it doesn't get mixed into JSON output
+        let cpu_time = match cpu_time {
+            Some(cpu_time) => format!("{:.3} ms CPU", cpu_time.as_secs_f64() * 1000.0),
+            None => "CPU time not available".to_string(),
+        };
+        eprintln!(
+            "{jobs} job{}: {:.3} ms wall-clock, {cpu_time}",
+            if jobs == 1 { "" } else { "s" },
+            start.elapsed().as_secs_f64() * 1000.0
+        );
+    }
+
+    if let Some(Err(e)) = state.map(|state| state.save(&args.state)) {
+        eprintln!("warning: {e:#}");
+    }
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
 }
@@ -0,0 +1,67 @@
+use std::{
+    collections::BTreeMap,
+    sync::{mpsc, Mutex},
+    thread,
+};
+
+/// Applies `f` to every item on a pool of `jobs` threads, passing the results to `output` in the original order.
+/// Each result is output as soon as it and every result before it are ready.
+pub fn map_in_order<T: Send, R: Send>(
+    items: Vec<T>,
+    jobs: usize,
+    f: impl Fn(T) -> R + Sync,
+    mut output: impl FnMut(R),
+) {
+    let queue = Mutex::new(items.into_iter().enumerate());
+    let (sender, receiver) = mpsc::channel::<(usize, R)>();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.max(1) {
+            let sender = sender.clone();
+            let (queue, f) = (&queue, &f);
+            scope.spawn(move || loop {
+                let Some((i, item)) = queue.lock().expect("a worker panicked while taking an item").next() else {
+                    break;
+                };
+                if sender.send((i, f(item))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender); // The receiver finishes once every worker has dropped its sender
+
+        let mut pending: BTreeMap<usize, R> = BTreeMap::new();
+        let mut next = 0;
+        for (i, result) in receiver {
+            pending.insert(i, result);
+            while let Some(result) = pending.remove(&next) {
+                output(result);
+                next += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_map_in_order() {
+        for jobs in [0, 1, 4] {
+            let mut results: Vec<u64> = Vec::new();
+            map_in_order(
+                (0..20).collect(),
+                jobs,
+                |i: u64| {
+                    // Make the earlier items finish last
+                    thread::sleep(Duration::from_millis(20 - i));
+                    i * i
+                },
+                |r| results.push(r),
+            );
+            assert_eq!(results, (0..20).map(|i| i * i).collect::<Vec<u64>>());
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use anyhow::{anyhow, Error, Result};
+use std::fmt::{self, Display};
+
+/// An error about one piece of the puzzle input. The piece is remembered by its address, so that [`diagnose`] can
+/// find it in the whole input later without every parser having to keep track of where its piece came from.
+#[derive(Debug)]
+pub struct Unexpected {
+    message: String,
+    address: usize,
+    len: usize,
+}
+
+impl Display for Unexpected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for Unexpected {}
+
+pub trait Locate<T> {
+    /// Points an error at `span`, which should be a slice of the puzzle input (rather than a copy of one).
+    /// An error that already points somewhere is left alone, as it will be more specific.
+    fn at(self, span: &str) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> Locate<T> for Result<T, E> {
+    fn at(self, span: &str) -> Result<T> {
+        self.map_err(|e| {
+            let e = e.into();
+            if e.downcast_ref::<Unexpected>().is_some() {
+                return e;
+            }
+            anyhow!(Unexpected { message: format!("{e:#}"), address: span.as_ptr() as usize, len: span.len() })
+        })
+    }
+}
+
+/// Where an error points, with the line of input it points into
+#[derive(Debug, PartialEq)]
+struct Location {
+    line: usize,
+    column: usize,
+    width: usize,
+    text: String,
+}
+
+impl Location {
+    fn find(input: &str, address: usize, len: usize) -> Option<Self> {
+        let offset = address.checked_sub(input.as_ptr() as usize).filter(|&offset| offset <= input.len())?;
+        let (before, after) = input.split_at_checked(offset)?;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let rest_of_line = after.lines().next().unwrap_or("");
+        let span = rest_of_line.get(..len).unwrap_or(rest_of_line);
+        Some(Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            width: span.chars().count().max(1),
+            text: format!("{}{rest_of_line}", &before[line_start..]),
+        })
+    }
+}
+
+/// An error shown underneath the line of input that caused it
+#[derive(Debug)]
+struct Diagnostic {
+    message: String,
+    location: Location,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Location { line, column, width, text } = &self.location;
+        let gutter = " ".repeat(line.to_string().len());
+        writeln!(f, "{}", self.message)?;
+        writeln!(f, "{gutter}--> line {line}, column {column}")?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{line} | {text}")?;
+        write!(f, "{gutter} | {}{}", " ".repeat(column - 1), "^".repeat(*width))
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+/// Adds the offending line of `input`, with the offending part underlined, to an error that points into it
+pub fn diagnose(error: Error, input: &str) -> Error {
+    let location = error.downcast_ref::<Unexpected>().and_then(|e| Location::find(input, e.address, e.len));
+    match location {
+        Some(location) => anyhow!(Diagnostic { message: format!("{error:#}"), location }),
+        None => error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    const INPUT: &str = "first line\nsecond λine\n";
+
+    #[test]
+    fn test_diagnose() {
+        let word = &INPUT[18..23];
+        assert_eq!(word, "λine");
+        let error = "x".parse::<u8>().at(word).context("Could not parse the line").unwrap_err();
+        assert_eq!(
+            format!("{}", diagnose(error, INPUT)),
+            "Could not parse the line: invalid digit found in string
+ --> line 2, column 8
+  |
+2 | second λine
+  |        ^^^^"
+        );
+    }
+
+    #[test]
+    fn test_innermost_location_wins() {
+        let line = &INPUT[11..23];
+        let error = Err::<(), _>(anyhow!("bad")).at(&line[7..]).at(line).unwrap_err();
+        assert_eq!(format!("{}", diagnose(error, INPUT)).lines().nth(1), Some(" --> line 2, column 8"));
+    }
+
+    #[test]
+    fn test_copied_input() {
+        let copy = INPUT.to_string();
+        let error = Err::<(), _>(anyhow!("bad")).at(&copy[11..23]).unwrap_err();
+        assert_eq!(format!("{}", diagnose(error, INPUT)), "bad");
+    }
+}
@@ -0,0 +1,211 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use crate::grid::Posn;
+
+/// A compass direction on a grid, where north is towards row 0 and east is towards higher columns
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// Every direction, clockwise from north
+    pub const ALL: [Direction; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Self::North => Self::West,
+            Self::East => Self::North,
+            Self::South => Self::East,
+            Self::West => Self::South,
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Self::North => Self::East,
+            Self::East => Self::South,
+            Self::South => Self::West,
+            Self::West => Self::North,
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::East => Self::West,
+            Self::South => Self::North,
+            Self::West => Self::East,
+        }
+    }
+
+    /// The offsets of all eight surrounding points, clockwise from north, including the diagonals between each
+    /// direction
+    pub fn all_with_diagonals() -> [Point; 8] {
+        Self::ALL.map(|d| [Point::from(d), Point::from(d) + d.turn_right()]).concat().try_into().unwrap()
+    }
+}
+
+/// A position (or offset) on an unbounded grid, which may be negative
+#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Point {
+    pub row: isize,
+    pub column: isize,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { row: 0, column: 0 };
+
+    pub fn new(row: isize, column: isize) -> Self {
+        Self { row, column }
+    }
+
+    pub fn manhattan(self, other: Point) -> usize {
+        self.row.abs_diff(other.row) + self.column.abs_diff(other.column)
+    }
+
+    /// The grid index of this point, or None if either coordinate is negative
+    pub fn to_posn(self) -> Option<Posn> {
+        Some((self.row.try_into().ok()?, self.column.try_into().ok()?))
+    }
+}
+
+impl From<Direction> for Point {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::North => Self::new(-1, 0),
+            Direction::East => Self::new(0, 1),
+            Direction::South => Self::new(1, 0),
+            Direction::West => Self::new(0, -1),
+        }
+    }
+}
+
+impl From<Posn> for Point {
+    fn from((row, column): Posn) -> Self {
+        Self::new(row as isize, column as isize)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+    fn add(self, rhs: Point) -> Point {
+        Point::new(self.row + rhs.row, self.column + rhs.column)
+    }
+}
+
+impl Add<Direction> for Point {
+    type Output = Point;
+    fn add(self, rhs: Direction) -> Point {
+        self + Point::from(rhs)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Point) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+    fn sub(self, rhs: Point) -> Point {
+        Point::new(self.row - rhs.row, self.column - rhs.column)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Point) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+    fn neg(self) -> Point {
+        Point::new(-self.row, -self.column)
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Point;
+    fn mul(self, rhs: isize) -> Point {
+        Point::new(self.row * rhs, self.column * rhs)
+    }
+}
+
+/// The number of grid points strictly inside a loop whose consecutive corners are joined by horizontal or vertical
+/// edges, with the last corner joined back to the first. The shoelace formula gives the area, and Pick's theorem
+/// (A = i + b/2 - 1) turns that into a count. Pick's theorem only holds for a loop that never crosses or doubles back
+/// on itself, so this is None for loops that obviously don't (a diagonal edge, no area, or more boundary than area).
+pub fn interior_points(corners: &[Point]) -> Option<usize> {
+    let mut twice_area: i128 = 0;
+    let mut boundary: u128 = 0;
+    for (a, b) in corners.iter().zip(corners.iter().cycle().skip(1)) {
+        if a.row != b.row && a.column != b.column {
+            return None;
+        }
+        let cross = a.row as i128 * b.column as i128 - b.row as i128 * a.column as i128;
+        twice_area = twice_area.checked_add(cross)?;
+        boundary += a.manhattan(*b) as u128;
+    }
+    if twice_area == 0 {
+        return None;
+    }
+    ((twice_area.unsigned_abs() + 2).checked_sub(boundary)? / 2).try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turns() {
+        for d in Direction::ALL {
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.turn_right().turn_right(), d.reverse());
+            assert_eq!(Point::from(d.reverse()), -Point::from(d));
+        }
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(
+            Direction::all_with_diagonals(),
+            [(-1, 0), (-1, 1), (0, 1), (1, 1), (1, 0), (1, -1), (0, -1), (-1, -1)].map(|(i, j)| Point::new(i, j))
+        );
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let p = Point::new(2, -3);
+        assert_eq!(p + Direction::North, Point::new(1, -3));
+        assert_eq!(p + Point::from(Direction::East) * 4, Point::new(2, 1));
+        assert_eq!(p - p, Point::ORIGIN);
+        assert_eq!(p.manhattan(Point::ORIGIN), 5);
+        assert_eq!(p.to_posn(), None);
+        assert_eq!(Point::new(2, 3).to_posn(), Some((2, 3)));
+        assert_eq!(Point::from((2, 3)), Point::new(2, 3));
+    }
+
+    #[test]
+    fn test_interior_points() {
+        let square = [(0, 0), (0, 4), (4, 4), (4, 0)].map(|(i, j)| Point::new(i, j));
+        assert_eq!(interior_points(&square), Some(9));
+        let mut reversed = square;
+        reversed.reverse();
+        assert_eq!(interior_points(&reversed), Some(9));
+        // An L shape, which is the 4 by 4 square without its top right 2 by 2 corner
+        let l = [(0, 0), (0, 2), (2, 2), (2, 4), (4, 4), (4, 0)].map(|(i, j)| Point::new(i, j));
+        assert_eq!(interior_points(&l), Some(5));
+
+        let back_and_forth = [(0, 0), (0, 5)].map(|(i, j)| Point::new(i, j));
+        assert_eq!(interior_points(&back_and_forth), None);
+        let diagonal = [(0, 0), (0, 4), (4, 0)].map(|(i, j)| Point::new(i, j));
+        assert_eq!(interior_points(&diagonal), None);
+        assert_eq!(interior_points(&[]), None);
+        // A square with a spike sticking out of it, which has more boundary than the area can account for
+        let spike = [(0, 0), (0, 1), (0, 9), (0, 1), (1, 1), (1, 0)].map(|(i, j)| Point::new(i, j));
+        assert_eq!(interior_points(&spike), None);
+    }
+}
@@ -0,0 +1,189 @@
+use anyhow::{bail, Context, Result};
+use std::{fmt::Write, path::Path};
+
+use crate::grid::{Grid, Posn};
+
+/// The width and height in pixels of each cell in PPM and PNG output
+pub const PIXELS_PER_CELL: usize = 4;
+
+/// A 24-bit colour
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Debug)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const DARK_GREY: Rgb = Rgb(64, 64, 64);
+    pub const GREY: Rgb = Rgb(128, 128, 128);
+    pub const LIGHT_GREY: Rgb = Rgb(192, 192, 192);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const RED: Rgb = Rgb(220, 40, 40);
+    pub const GREEN: Rgb = Rgb(40, 180, 70);
+    pub const YELLOW: Rgb = Rgb(250, 210, 40);
+
+    /// A grey between black (0) and white (255)
+    pub fn grey(level: u8) -> Self {
+        Self(level, level, level)
+    }
+}
+
+impl From<u32> for Rgb {
+    /// Takes a colour written as 0xRRGGBB
+    fn from(value: u32) -> Self {
+        Self((value >> 16) as u8, (value >> 8) as u8, value as u8)
+    }
+}
+
+/// A rendered grid, with one colour per cell
+pub type Image = Grid<Rgb>;
+
+/// Colours every cell in `cells` on top of whatever is already there
+pub fn overlay(image: &mut Image, cells: impl IntoIterator<Item = Posn>, colour: Rgb) {
+    for posn in cells {
+        if let Some(cell) = image.get_mut(posn) {
+            *cell = colour;
+        }
+    }
+}
+
+/// Writes the image to `path`, choosing PPM, PNG or SVG from its extension
+pub fn save(image: &Image, path: &Path) -> Result<()> {
+    let bytes = match path.extension().and_then(|e| e.to_str()) {
+        Some("ppm") => to_ppm(image, PIXELS_PER_CELL),
+        Some("png") => to_png(image, PIXELS_PER_CELL),
+        Some("svg") => to_svg(image).into_bytes(),
+        _ => bail!("Cannot tell the image format of {} (expected .ppm, .png or .svg)", path.display()),
+    };
+    std::fs::write(path, bytes).with_context(|| format!("Could not write {}", path.display()))
+}
+
+/// The image's pixels row by row, with each cell scaled up to `scale` by `scale` pixels
+fn scanlines(image: &Image, scale: usize) -> impl Iterator<Item = Vec<u8>> + '_ {
+    image.rows().flat_map(move |row| {
+        let line: Vec<u8> = row.iter().flat_map(|&Rgb(r, g, b)| [r, g, b].repeat(scale)).collect();
+        std::iter::repeat_n(line, scale)
+    })
+}
+
+/// A binary (P6) portable pixmap
+pub fn to_ppm(image: &Image, scale: usize) -> Vec<u8> {
+    let mut ppm = format!("P6\n{} {}\n255\n", image.width() * scale, image.height() * scale).into_bytes();
+    scanlines(image, scale).for_each(|line| ppm.extend(line));
+    ppm
+}
+
+/// A truecolour PNG, stored without compression so that it needs no dependencies
+pub fn to_png(image: &Image, scale: usize) -> Vec<u8> {
+    let mut raw: Vec<u8> = Vec::new();
+    for line in scanlines(image, scale) {
+        raw.push(0); // No filtering
+        raw.extend(line);
+    }
+
+    // A zlib stream of uncompressed deflate blocks, which can each hold up to 65535 bytes
+    let mut zlib: Vec<u8> = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = if raw.is_empty() { vec![&[]] } else { raw.chunks(65535).collect() };
+    for (i, block) in blocks.iter().enumerate() {
+        zlib.push(u8::from(i == blocks.len() - 1));
+        zlib.extend((block.len() as u16).to_le_bytes());
+        zlib.extend((!(block.len() as u16)).to_le_bytes());
+        zlib.extend(*block);
+    }
+    zlib.extend(adler32(&raw).to_be_bytes());
+
+    let mut header: Vec<u8> = Vec::new();
+    header.extend(((image.width() * scale) as u32).to_be_bytes());
+    header.extend(((image.height() * scale) as u32).to_be_bytes());
+    header.extend([8, 2, 0, 0, 0]); // 8 bits per channel, RGB, and the only compression, filter and interlace methods
+
+    let mut png: Vec<u8> = b"\x89PNG\r\n\x1a\n".to_vec();
+    for (kind, data) in [(b"IHDR", header), (b"IDAT", zlib), (b"IEND", Vec::new())] {
+        png.extend((data.len() as u32).to_be_bytes());
+        let start = png.len();
+        png.extend(kind);
+        png.extend(data);
+        let crc = crc32(&png[start..]);
+        png.extend(crc.to_be_bytes());
+    }
+    png
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb88320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+/// An SVG with one unit per cell, and one rectangle per horizontal run of the same colour
+pub fn to_svg(image: &Image) -> String {
+    let (width, height) = (image.width(), image.height());
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {width} {height}\" width=\"{}\" height=\"{}\" \
+         shape-rendering=\"crispEdges\">\n",
+        width * PIXELS_PER_CELL,
+        height * PIXELS_PER_CELL
+    );
+    for (i, row) in image.rows().enumerate() {
+        let mut j = 0;
+        for run in row.chunk_by(|a, b| a == b) {
+            let Rgb(r, g, b) = run[0];
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{j}\" y=\"{i}\" width=\"{}\" height=\"1\" fill=\"#{r:02x}{g:02x}{b:02x}\"/>",
+                run.len()
+            );
+            j += run.len();
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Image {
+        let mut image = Grid::new(3, 2, Rgb::BLACK);
+        overlay(&mut image, [(0, 1), (0, 2), (5, 5)], Rgb::from(0x102030));
+        image
+    }
+
+    #[test]
+    fn test_ppm() {
+        let ppm = to_ppm(&example(), 2);
+        assert!(ppm.starts_with(b"P6\n6 4\n255\n"));
+        assert_eq!(ppm.len(), "P6\n6 4\n255\n".len() + 6 * 4 * 3);
+        assert_eq!(ppm[11..20], [0, 0, 0, 0, 0, 0, 0x10, 0x20, 0x30]);
+    }
+
+    #[test]
+    fn test_png() {
+        let png = to_png(&example(), 1);
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x03\0\0\0\x02"));
+        assert!(png.ends_with(b"\0\0\0\0IEND\xae\x42\x60\x82"));
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+    }
+
+    #[test]
+    fn test_svg() {
+        let svg = to_svg(&example());
+        assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"1\" height=\"1\" fill=\"#000000\"/>"));
+        assert!(svg.contains("<rect x=\"1\" y=\"0\" width=\"2\" height=\"1\" fill=\"#102030\"/>"));
+        assert!(svg.contains("<rect x=\"0\" y=\"1\" width=\"3\" height=\"1\" fill=\"#000000\"/>"));
+    }
+}
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use crate::{answers::Verdict, solution::Unimplemented};
+
+/// The outcome of running one part, for machine-readable output
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub elapsed: Duration,
+    pub verdict: Verdict,
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn json_option(value: Option<String>) -> String {
+    value.unwrap_or_else(|| "null".to_string())
+}
+
+impl Record {
+    /// Formats the record as a single line of JSON
+    pub fn to_json(&self) -> String {
+        let error = match &self.verdict {
+            Verdict::Unsolved => Some(Unimplemented.to_string()),
+            Verdict::Error(e) => Some(e.clone()),
+            _ => None,
+        };
+        format!(
+            "{{\"day\":{},\"part\":{},\"answer\":{},\"elapsed_ms\":{:.3},\"error\":{},\"matched\":{}}}",
+            self.day,
+            self.part,
+            json_option(self.verdict.answer().map(json_string)),
+            self.elapsed.as_secs_f64() * 1000.0,
+            json_option(error.as_deref().map(json_string)),
+            json_option(self.verdict.matched().map(|m| m.to_string())),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("plain"), "\"plain\"");
+        assert_eq!(json_string("a \"quote\"\\\n"), "\"a \\\"quote\\\"\\\\\\n\"");
+        assert_eq!(json_string("\u{1}"), "\"\\u0001\"");
+    }
+
+    #[test]
+    fn test_to_json() {
+        let record = Record {
+            day: 1,
+            part: 2,
+            elapsed: Duration::from_micros(1500),
+            verdict: Verdict::Pass { actual: "281".to_string() },
+        };
+        assert_eq!(
+            record.to_json(),
+            r#"{"day":1,"part":2,"answer":"281","elapsed_ms":1.500,"error":null,"matched":true}"#
+        );
+
+        let record = Record { day: 12, part: 1, elapsed: Duration::ZERO, verdict: Verdict::Unsolved };
+        assert_eq!(
+            record.to_json(),
+            r#"{"day":12,"part":1,"answer":null,"elapsed_ms":0.000,"error":"not implemented","matched":null}"#
+        );
+    }
+}
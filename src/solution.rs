@@ -0,0 +1,862 @@
+use anyhow::{anyhow, bail, Context, Error, Result};
+use std::{
+    any::Any,
+    fmt::Display,
+    ops::RangeInclusive,
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::{
+    day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13, day14, day15, day16,
+    day17, day18, day19, day20, day21, day22, day23, day24, day25, generate, grid::Grid, parse, render::Image,
+    visualize::Frame,
+};
+
+/// The answer to one part of a puzzle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Unsigned(u64),
+    Signed(i64),
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Self {
+        Self::Unsigned(value.into())
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Self::Unsigned(value as u64)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(value: isize) -> Self {
+        Self::Signed(value as i64)
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsigned(value) => write!(f, "{value}"),
+            Self::Signed(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// The error returned by a part that has not been solved yet
+#[derive(Debug)]
+pub struct Unimplemented;
+
+impl Display for Unimplemented {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "not implemented")
+    }
+}
+
+impl std::error::Error for Unimplemented {}
+
+/// A single day's puzzle. Any parameters that are not part of the puzzle input live on the implementing type.
+pub trait Solution: Send + Sync {
+    fn day(&self) -> u8;
+
+    /// Parses the input without solving either part, so that parsing can be timed on its own
+    fn parse(&self, _input: &str) -> Result<()> {
+        Err(anyhow!(Unimplemented))
+    }
+
+    fn part1(&self, _input: &str) -> Result<Answer> {
+        Err(anyhow!(Unimplemented))
+    }
+
+    fn part2(&self, _input: &str) -> Result<Answer> {
+        Err(anyhow!(Unimplemented))
+    }
+
+    fn part(&self, part: u8, input: &str) -> Result<Answer> {
+        match part {
+            1 => self.part1(input),
+            2 => self.part2(input),
+            _ => Err(anyhow!("There is no part {part}")),
+        }
+    }
+
+    /// Draws the puzzle's grid, highlighting whatever is interesting about the given part
+    fn render(&self, _part: u8, _input: &str) -> Result<Image> {
+        Err(anyhow!("Day {} cannot be rendered", self.day()))
+    }
+
+    /// Replays the given part's simulation step by step, passing each frame to `on_frame`
+    fn visualize(&self, _part: u8, _input: &str, _on_frame: &mut dyn FnMut(Frame)) -> Result<()> {
+        Err(anyhow!("Day {} cannot be visualized", self.day()))
+    }
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    fn day(&self) -> u8 {
+        1
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day01::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day01::part2(input)?.into())
+    }
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    fn day(&self) -> u8 {
+        2
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        day02::parse_input(input)?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day02::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day02::part2(input)?.into())
+    }
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    fn day(&self) -> u8 {
+        3
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        input.parse::<Grid<char>>()?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day03::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day03::part2(input)?.into())
+    }
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    fn day(&self) -> u8 {
+        4
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        for line in input.lines() {
+            line.parse::<day04::Card>()?;
+        }
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day04::part1(input).into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day04::part2(input).into())
+    }
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    fn day(&self) -> u8 {
+        5
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        input.parse::<day05::Almanac>()?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day05::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day05::part2(input)?.into())
+    }
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    fn day(&self) -> u8 {
+        6
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        day06::parse_input(input)?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day06::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day06::part2(input)?.into())
+    }
+}
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    fn day(&self) -> u8 {
+        7
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        for line in input.lines() {
+            line.parse::<day07::HandP1>()?;
+            line.parse::<day07::HandP2>()?;
+        }
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day07::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day07::part2(input)?.into())
+    }
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    fn day(&self) -> u8 {
+        8
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        let (instructions, graph) = input.split_once("\n\n").context("Could not split instructions and graph")?;
+        day08::parse_instructions(instructions)?;
+        graph.parse::<day08::GraphP1>()?;
+        graph.parse::<day08::GraphP2>()?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day08::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day08::part2(input)?.into())
+    }
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    fn day(&self) -> u8 {
+        9
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        day09::parse_input(input)?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day09::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day09::part2(input)?.into())
+    }
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    fn day(&self) -> u8 {
+        10
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        input.parse::<day10::Matrix>()?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day10::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day10::part2(input)?.into())
+    }
+
+    fn render(&self, _part: u8, input: &str) -> Result<Image> {
+        day10::render(input)
+    }
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    fn day(&self) -> u8 {
+        11
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        day11::parse_galaxies(input, 2)?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day11::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day11::part2(input)?.into())
+    }
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    fn day(&self) -> u8 {
+        12
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        day12::parse_input(input)?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day12::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day12::part2(input)?.into())
+    }
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    fn day(&self) -> u8 {
+        13
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        day13::parse_input(input)?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day13::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day13::part2(input)?.into())
+    }
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    fn day(&self) -> u8 {
+        14
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        input.parse::<day14::Platform>()?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day14::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day14::part2(input)?.into())
+    }
+
+    fn visualize(&self, part: u8, input: &str, on_frame: &mut dyn FnMut(Frame)) -> Result<()> {
+        day14::visualize(input, part, on_frame)
+    }
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    fn day(&self) -> u8 {
+        15
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day15::part1(input).into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day15::part2(input)?.into())
+    }
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    fn day(&self) -> u8 {
+        16
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        input.parse::<day16::Contraption>()?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day16::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day16::part2(input)?.into())
+    }
+
+    fn render(&self, part: u8, input: &str) -> Result<Image> {
+        day16::render(input, part)
+    }
+
+    fn visualize(&self, part: u8, input: &str, on_frame: &mut dyn FnMut(Frame)) -> Result<()> {
+        day16::visualize(input, part, on_frame)
+    }
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    fn day(&self) -> u8 {
+        17
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        input.parse::<day17::City>()?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day17::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day17::part2(input)?.into())
+    }
+
+    fn render(&self, part: u8, input: &str) -> Result<Image> {
+        day17::render(input, part)
+    }
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    fn day(&self) -> u8 {
+        18
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        input.parse::<day18::DigPlan>()?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day18::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day18::part2(input)?.into())
+    }
+
+    fn render(&self, _part: u8, input: &str) -> Result<Image> {
+        day18::render(input)
+    }
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    fn day(&self) -> u8 {
+        19
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        day19::parse_input(input)?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day19::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day19::part2(input)?.into())
+    }
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    fn day(&self) -> u8 {
+        20
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        day20::parse_input(input)?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day20::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day20::part2(input)?.into())
+    }
+
+    fn visualize(&self, part: u8, input: &str, on_frame: &mut dyn FnMut(Frame)) -> Result<()> {
+        day20::visualize(input, part, on_frame)
+    }
+}
+
+/// Day 21 is parameterised by the number of steps the elf takes in each part
+pub struct Day21 {
+    pub steps: usize,
+    pub part2_steps: usize,
+}
+
+impl Default for Day21 {
+    fn default() -> Self {
+        Self { steps: 64, part2_steps: 26501365 }
+    }
+}
+
+impl Solution for Day21 {
+    fn day(&self) -> u8 {
+        21
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        input.parse::<day21::Map>()?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day21::part1(input, self.steps)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day21::part2(input, self.part2_steps)?.into())
+    }
+
+    fn render(&self, _part: u8, input: &str) -> Result<Image> {
+        day21::render(input, self.steps)
+    }
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    fn day(&self) -> u8 {
+        22
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        day22::parse_input(input)?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day22::part1(input)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day22::part2(input)?.into())
+    }
+
+    fn visualize(&self, part: u8, input: &str, on_frame: &mut dyn FnMut(Frame)) -> Result<()> {
+        day22::visualize(input, part, on_frame)
+    }
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    fn day(&self) -> u8 {
+        23
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        input.parse::<day23::Map>()?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day23::part1(input)?.into())
+    }
+}
+
+/// Day 24 is parameterised by the area in which to look for hailstone collisions
+pub struct Day24 {
+    pub test_area: RangeInclusive<f64>,
+}
+
+impl Default for Day24 {
+    fn default() -> Self {
+        Self { test_area: 2e14..=4e14 }
+    }
+}
+
+impl Solution for Day24 {
+    fn day(&self) -> u8 {
+        24
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        day24::parse_input(input)?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day24::part1(input, self.test_area.clone())?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        Ok(day24::part2(input)?.into())
+    }
+}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    fn day(&self) -> u8 {
+        25
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        input.parse::<day25::Graph>()?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        Ok(day25::part1(input)?.into())
+    }
+}
+
+/// Runs one part of a solution, converting a panic into an error so that other days can still run.
+/// A `todo!()` counts as [`Unimplemented`]. Errors that point into the input are shown with the line they point at.
+pub fn solve(solution: &dyn Solution, part: u8, input: &str) -> Result<Answer> {
+    panic::catch_unwind(AssertUnwindSafe(|| solution.part(part, input)))
+        .unwrap_or_else(|payload| Err(panic_error(payload.as_ref())))
+        .map_err(|e| parse::diagnose(e, input))
+}
+
+fn panic_error(payload: &(dyn Any + Send)) -> Error {
+    let message =
+        payload.downcast_ref::<&str>().copied().or_else(|| payload.downcast_ref::<String>().map(String::as_str));
+    match message {
+        Some(m) if m.starts_with("not yet implemented") => anyhow!(Unimplemented),
+        _ => anyhow!("solution panicked"),
+    }
+}
+
+/// Whether a part has been solved, found by solving a small generated input rather than needing the real one
+pub fn is_implemented(solution: &dyn Solution, part: u8) -> bool {
+    let day = solution.day();
+    let input = generate::input(day, 0, (generate::default_size(day) / 10).max(3)).unwrap_or_default();
+    !solve(solution, part, &input).is_err_and(|e| e.is::<Unimplemented>())
+}
+
+/// Every day, in order, with the parameters from the puzzle descriptions
+pub fn registry() -> Vec<Box<dyn Solution>> {
+    vec![
+        Box::new(Day01),
+        Box::new(Day02),
+        Box::new(Day03),
+        Box::new(Day04),
+        Box::new(Day05),
+        Box::new(Day06),
+        Box::new(Day07),
+        Box::new(Day08),
+        Box::new(Day09),
+        Box::new(Day10),
+        Box::new(Day11),
+        Box::new(Day12),
+        Box::new(Day13),
+        Box::new(Day14),
+        Box::new(Day15),
+        Box::new(Day16),
+        Box::new(Day17),
+        Box::new(Day18),
+        Box::new(Day19),
+        Box::new(Day20),
+        Box::new(Day21::default()),
+        Box::new(Day22),
+        Box::new(Day23),
+        Box::new(Day24::default()),
+        Box::new(Day25),
+    ]
+}
+
+/// The solution for a day with some of its parameters overridden, e.g. for an example that takes fewer steps
+pub fn with_params(day: u8, params: &[(String, String)]) -> Result<Box<dyn Solution>> {
+    let mut day21 = Day21::default();
+    let mut day24 = Day24::default();
+    for (key, value) in params {
+        match (day, key.as_str()) {
+            (21, "steps") => day21.steps = value.parse().with_context(|| format!("Invalid number of steps {value}"))?,
+            (21, "part2_steps") => {
+                day21.part2_steps = value.parse().with_context(|| format!("Invalid number of steps {value}"))?
+            }
+            (24, "test_area") => {
+                let (start, end) =
+                    value.split_once("..=").with_context(|| format!("Expected start..=end, got {value}"))?;
+                day24.test_area = start.trim().parse()?..=end.trim().parse()?;
+            }
+            _ => bail!("Day {day} has no parameter {key}"),
+        }
+    }
+    match day {
+        21 => Ok(Box::new(day21)),
+        24 => Ok(Box::new(day24)),
+        _ => registry().into_iter().find(|s| s.day() == day).with_context(|| format!("There is no day {day}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::{self, Rng};
+
+    #[test]
+    fn test_registry() {
+        let days: Vec<u8> = registry().iter().map(|s| s.day()).collect();
+        assert_eq!(days, (1..=25).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_unimplemented() {
+        let error = Day25.part2("").unwrap_err();
+        assert!(error.is::<Unimplemented>());
+    }
+
+    /// The puzzle input with a few characters changed, removed, repeated or cut off
+    fn mutate(rng: &mut Rng, input: &str) -> String {
+        const REPLACEMENTS: [&str; 12] =
+            ["\n", " ", "-", ",", ":", "#", "A", "x", "é", "", "0", "99999999999999999999"];
+        let mut chars: Vec<char> = input.chars().collect();
+        for _ in 0..rng.range(1..4) {
+            if chars.is_empty() {
+                break;
+            }
+            let at = rng.range(0..chars.len());
+            let end = rng.range(at..chars.len().min(at + 20) + 1);
+            match rng.range(0..4) {
+                0 => drop(chars.splice(at..end, rng.choose(&REPLACEMENTS).chars())),
+                1 => chars.truncate(at),
+                2 => drop(chars.splice(at..at, chars[at..end].to_vec())),
+                _ => drop(chars.splice(at..at + 1, rng.choose(&REPLACEMENTS).chars())),
+            }
+        }
+        chars.into_iter().collect()
+    }
+
+    #[test]
+    fn test_parsers_do_not_panic() {
+        for solution in registry() {
+            let day = solution.day();
+            generate::check_cases(200, |rng| {
+                let input = generate::input(day, rng.next_u64(), (generate::default_size(day) / 20).max(3)).unwrap();
+                let input = mutate(rng, &input);
+                // Days without a separate parsing step are cheap enough to solve instead
+                if solution.parse(&input).is_err_and(|e| e.is::<Unimplemented>()) {
+                    let _ = (solution.part1(&input), solution.part2(&input));
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn test_parse_errors_point_into_the_input() {
+        let cases = [
+            (2_u8, None, "Game 1: 3 blue\nGame 2: 4 purple\n", "line 2, column 11"),
+            (4, None, "Card 1: 41 48 | 83 86\nCard 2: 13 x | 61\n", "line 2, column 12"),
+            (6, None, "Time: 7 15\nDistance: 9 4o\n", "line 2, column 13"),
+            (12, Some(2), "???.### 1,1,3\n.??x 1\n", "line 2, column 4"),
+            (13, None, "#.\n.#\n\n#.\n.\n", "line 5, column 1"),
+            (15, Some(2), "rn=1,cm=x", "line 1, column 9"),
+            (16, None, ".|\n.a\n", "line 2, column 2"),
+            (17, None, "12\n3x\n", "line 2, column 2"),
+            (20, None, "broadcaster -> a\n%a\n", "line 2, column 1"),
+            (22, None, "1,0,1~1,2,1\n0,0,2~2,x,2\n", "line 2, column 9"),
+            (24, None, "19, 13, 30 @ -2, 1, -2\n18, 19, 22 @ -1, -1, x\n", "line 2, column 22"),
+            (25, None, "jqt: rhn xhk\nrsh: fr\n", "line 2, column 6"),
+        ];
+        // Day 12 part 2 unfolds the records after parsing them, and day 15 has no separate parsing step
+        for (day, part, input, location) in cases {
+            let solution = &registry()[usize::from(day) - 1];
+            let result = match part {
+                Some(part) => solve(solution.as_ref(), part, input).map(drop),
+                None => solution.parse(input).map_err(|e| parse::diagnose(e, input)),
+            };
+            let error = result.expect_err(&format!("day {day}")).to_string();
+            assert_eq!(error.lines().nth(1), Some(format!(" --> {location}").as_str()), "day {day}:\n{error}");
+        }
+    }
+
+    #[test]
+    fn test_todo_is_unimplemented() {
+        struct Todo;
+        impl Solution for Todo {
+            fn day(&self) -> u8 {
+                1
+            }
+
+            fn part1(&self, _input: &str) -> Result<Answer> {
+                todo!()
+            }
+
+            fn part2(&self, _input: &str) -> Result<Answer> {
+                panic!("oops")
+            }
+        }
+        assert!(solve(&Todo, 1, "").unwrap_err().is::<Unimplemented>());
+        assert_eq!(solve(&Todo, 2, "").unwrap_err().to_string(), "solution panicked");
+    }
+
+    #[test]
+    fn test_is_implemented() {
+        assert!(is_implemented(&Day01, 1));
+        assert!(!is_implemented(&Day25, 2));
+        assert!(!is_implemented(&Day23, 2));
+    }
+
+    #[test]
+    fn test_params() {
+        let day21 = with_params(21, &[("steps".to_string(), "6".to_string())]).unwrap();
+        let input = crate::examples::input(21, "example");
+        assert_eq!(day21.part1(&input).unwrap(), Answer::Unsigned(16));
+        assert!(with_params(7, &[("steps".to_string(), "6".to_string())]).is_err());
+    }
+}
@@ -0,0 +1,207 @@
+use anyhow::{bail, Context, Error, Result};
+use std::{collections::BTreeMap, fmt::Display, str::FromStr, time::Duration};
+
+use crate::answers::{self, Entry, Verdict};
+
+/// The last answer computed for one part, and how its submissions have gone
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct PartState {
+    /// Empty if the part has been submitted but never run
+    pub answer: String,
+    pub elapsed: Duration,
+    /// Whether the answer matched the known answer, if there was one
+    pub verified: Option<bool>,
+    pub wrong_submissions: u32,
+    /// No answer can be submitted until this time, in seconds since the Unix epoch
+    pub locked_until: Option<u64>,
+}
+
+/// The outcome of the last `run` or `verify` of each part, and of `submit`, kept between runs in the same subset of
+/// TOML as the known answers:
+///
+/// ```toml
+/// [day01]
+/// part1 = "55130"
+/// part1_elapsed_ms = 0.412
+/// part1_verified = true
+/// part1_wrong_submissions = 1
+/// part1_locked_until = 1700000060
+/// ```
+#[derive(Default, Debug, PartialEq)]
+pub struct State(BTreeMap<(u8, u8), PartState>);
+
+impl FromStr for State {
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Self> {
+        let mut state = Self::default();
+        for Entry { line, day, part, field, value } in answers::entries(input)? {
+            let part_state = state.0.entry((day, part)).or_default();
+            match field {
+                "" => part_state.answer = value.to_string(),
+                "elapsed_ms" => {
+                    let millis: f64 = value.parse().with_context(|| format!("line {line}: invalid time"))?;
+                    part_state.elapsed = Duration::from_secs_f64(millis.max(0.0) / 1000.0);
+                }
+                "verified" => {
+                    part_state.verified = Some(value.parse().with_context(|| format!("line {line}: invalid bool"))?)
+                }
+                "wrong_submissions" => {
+                    part_state.wrong_submissions =
+                        value.parse().with_context(|| format!("line {line}: invalid number of submissions"))?
+                }
+                "locked_until" => {
+                    part_state.locked_until = Some(value.parse().with_context(|| format!("line {line}: invalid time"))?)
+                }
+                f => bail!("line {line}: unknown field {f}"),
+            }
+        }
+        Ok(state)
+    }
+}
+
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# Written by `run`, `verify` and `submit`, and shown by `status`")?;
+        let mut day = None;
+        for (&(d, part), part_state) in &self.0 {
+            if day != Some(d) {
+                write!(f, "\n[day{d:02}]\n")?;
+                day = Some(d);
+            }
+            if !part_state.answer.is_empty() {
+                writeln!(f, "part{part} = \"{}\"", part_state.answer)?;
+                writeln!(f, "part{part}_elapsed_ms = {:.3}", part_state.elapsed.as_secs_f64() * 1000.0)?;
+            }
+            if let Some(verified) = part_state.verified {
+                writeln!(f, "part{part}_verified = {verified}")?;
+            }
+            if part_state.wrong_submissions > 0 {
+                writeln!(f, "part{part}_wrong_submissions = {}", part_state.wrong_submissions)?;
+            }
+            if let Some(locked_until) = part_state.locked_until {
+                writeln!(f, "part{part}_locked_until = {locked_until}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl State {
+    pub fn load(path: &str) -> Result<Self> {
+        std::fs::read_to_string(path).with_context(|| format!("Could not read {path}"))?.parse()
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        std::fs::write(path, self.to_string()).with_context(|| format!("Could not write {path}"))
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&PartState> {
+        self.0.get(&(day, part))
+    }
+
+    pub fn get_mut(&mut self, day: u8, part: u8) -> &mut PartState {
+        self.0.entry((day, part)).or_default()
+    }
+
+    /// Remembers the answer from a run. Runs without an answer leave the last one in place.
+    pub fn record(&mut self, day: u8, part: u8, elapsed: Duration, verdict: &Verdict) {
+        if let Some(answer) = verdict.answer() {
+            let part_state = self.get_mut(day, part);
+            part_state.answer = answer.to_string();
+            part_state.elapsed = elapsed;
+            part_state.verified = verdict.matched();
+        }
+    }
+}
+
+pub fn print_status_header() {
+    println!("{:<6} {:<16} {:>20} {:>12} {:>9}", "", "status", "last answer", "time (ms)", "verified");
+}
+
+pub fn status_row(day: u8, part: u8, implemented: bool, part_state: Option<&PartState>) -> String {
+    let status = if implemented { "implemented" } else { "not implemented" };
+    let (answer, millis, verified) = match part_state.filter(|p| !p.answer.is_empty()) {
+        Some(p) => (
+            p.answer.as_str(),
+            format!("{:.3}", p.elapsed.as_secs_f64() * 1000.0),
+            match p.verified {
+                Some(true) => "yes",
+                Some(false) => "NO",
+                None => "unknown",
+            },
+        ),
+        None => ("-", "-".to_string(), "-"),
+    };
+    format!("{:<6} {status:<16} {answer:>20} {millis:>12} {verified:>9}", format!("{day:02}-{part}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Answer;
+
+    const EXAMPLE: &str = "[day01]
+part1 = \"55130\"
+part1_elapsed_ms = 0.412
+part1_verified = true
+
+[day12]
+part1_wrong_submissions = 2
+part1_locked_until = 1700000060
+part2 = \"1\"
+part2_elapsed_ms = 2.000
+";
+
+    #[test]
+    fn test_parse_state() {
+        let state: State = EXAMPLE.parse().unwrap();
+        assert_eq!(
+            state.get(1, 1),
+            Some(&PartState {
+                answer: "55130".to_string(),
+                elapsed: Duration::from_micros(412),
+                verified: Some(true),
+                ..PartState::default()
+            })
+        );
+        assert_eq!(state.get(12, 2).unwrap().verified, None);
+        assert_eq!(state.get(12, 1).unwrap().locked_until, Some(1700000060));
+        assert_eq!(state.get(13, 1), None);
+        assert!("[day01]\npart1_colour = red".parse::<State>().is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let state: State = EXAMPLE.parse().unwrap();
+        assert_eq!(state.to_string().parse::<State>().unwrap(), state);
+    }
+
+    #[test]
+    fn test_record() {
+        let mut state = State::default();
+        let verdict = Verdict::new(Some("142"), Ok(Answer::Unsigned(142)));
+        state.record(1, 1, Duration::from_millis(3), &verdict);
+        state.record(1, 1, Duration::from_millis(5), &Verdict::Unsolved);
+        assert_eq!(state.get(1, 1).unwrap().verified, Some(true));
+        assert_eq!(state.get(1, 1).unwrap().elapsed, Duration::from_millis(3));
+        state.get_mut(1, 2).wrong_submissions = 1;
+        state.record(1, 2, Duration::from_millis(5), &verdict);
+        assert_eq!(state.get(1, 2).unwrap().wrong_submissions, 1);
+    }
+
+    #[test]
+    fn test_status_row() {
+        let part_state =
+            PartState { answer: "281".to_string(), elapsed: Duration::from_micros(1500), ..PartState::default() };
+        assert_eq!(
+            status_row(1, 2, true, Some(&part_state)),
+            "01-2   implemented                       281        1.500   unknown"
+        );
+        assert_eq!(
+            status_row(12, 1, false, None),
+            "12-1   not implemented                     -            -         -"
+        );
+        let submitted = PartState { wrong_submissions: 1, ..PartState::default() };
+        assert_eq!(status_row(12, 1, false, Some(&submitted)), status_row(12, 1, false, None));
+    }
+}
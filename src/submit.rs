@@ -0,0 +1,98 @@
+//! A stand-in for submitting answers on the Advent of Code website, which checks them against the known answers and
+//! makes you wait after a wrong one in the same way.
+
+use anyhow::{bail, Result};
+use std::{fmt::Display, time::Duration};
+
+use crate::state::PartState;
+
+/// What the website would say about a submitted answer
+#[derive(Debug, PartialEq)]
+pub enum Feedback {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    /// There is no known answer to check against
+    Unknown,
+}
+
+impl Feedback {
+    pub fn new(expected: Option<&str>, answer: &str) -> Self {
+        let Some(expected) = expected else {
+            return Self::Unknown;
+        };
+        if answer == expected {
+            return Self::Correct;
+        }
+        match (answer.parse::<i128>(), expected.parse::<i128>()) {
+            (Ok(answer), Ok(expected)) if answer > expected => Self::TooHigh,
+            (Ok(answer), Ok(expected)) if answer < expected => Self::TooLow,
+            _ => Self::Wrong,
+        }
+    }
+
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Self::TooHigh | Self::TooLow | Self::Wrong)
+    }
+}
+
+impl Display for Feedback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Correct => write!(f, "that's the right answer"),
+            Self::TooHigh => write!(f, "that's not the right answer; your answer is too high"),
+            Self::TooLow => write!(f, "that's not the right answer; your answer is too low"),
+            Self::Wrong => write!(f, "that's not the right answer"),
+            Self::Unknown => write!(f, "unknown, as there is no known answer to check it against"),
+        }
+    }
+}
+
+/// How long to wait after this many wrong answers: a minute for each of the first few, then five minutes
+pub fn cooldown(wrong_submissions: u32) -> Duration {
+    Duration::from_secs(if wrong_submissions < 5 { 60 } else { 5 * 60 })
+}
+
+/// Checks a submitted answer, and starts a cooldown if it is wrong. Nothing is accepted while a cooldown is running.
+/// `now` is in seconds since the Unix epoch.
+pub fn submit(part_state: &mut PartState, expected: Option<&str>, answer: &str, now: u64) -> Result<Feedback> {
+    if let Some(locked_until) = part_state.locked_until.filter(|&until| until > now) {
+        bail!("You gave a wrong answer too recently; wait {}s before trying again", locked_until - now);
+    }
+    let feedback = Feedback::new(expected, answer);
+    if feedback.is_wrong() {
+        part_state.wrong_submissions += 1;
+        part_state.locked_until = Some(now + cooldown(part_state.wrong_submissions).as_secs());
+    }
+    Ok(feedback)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feedback() {
+        assert_eq!(Feedback::new(Some("142"), "142"), Feedback::Correct);
+        assert_eq!(Feedback::new(Some("142"), "143"), Feedback::TooHigh);
+        assert_eq!(Feedback::new(Some("142"), "-1"), Feedback::TooLow);
+        assert_eq!(Feedback::new(Some("DBCA"), "ABCD"), Feedback::Wrong);
+        assert_eq!(Feedback::new(None, "142"), Feedback::Unknown);
+    }
+
+    #[test]
+    fn test_submit_waits_after_wrong_answers() {
+        let mut part_state = PartState::default();
+        assert_eq!(submit(&mut part_state, Some("142"), "100", 1000).unwrap(), Feedback::TooLow);
+        assert_eq!(part_state.locked_until, Some(1060));
+        assert!(submit(&mut part_state, Some("142"), "142", 1059).is_err());
+        for now in [1060, 1120, 1180, 1240] {
+            submit(&mut part_state, Some("142"), "200", now).unwrap();
+        }
+        assert_eq!((part_state.wrong_submissions, part_state.locked_until), (5, Some(1540)));
+        assert_eq!(submit(&mut part_state, Some("142"), "142", 1540).unwrap(), Feedback::Correct);
+        assert_eq!(submit(&mut part_state, None, "1", 1540).unwrap(), Feedback::Unknown);
+        assert_eq!(part_state.wrong_submissions, 5);
+    }
+}
@@ -0,0 +1,173 @@
+use anyhow::{anyhow, Context, Result};
+use std::{
+    fmt::Write as _,
+    io::{self, IsTerminal, Read, Write},
+    process::{Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{grid::Grid, render::Rgb};
+
+/// One step of a simulation, drawn as coloured characters with a line of text underneath
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Frame {
+    pub cells: Grid<(char, Rgb)>,
+    pub caption: String,
+}
+
+impl Frame {
+    /// A frame from lines of text that all share one colour, padded out to the longest line
+    pub fn from_lines(lines: &[String], colour: Rgb, caption: String) -> Result<Self> {
+        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let rows = lines
+            .iter()
+            .map(|l| l.chars().chain(std::iter::repeat(' ')).take(width).map(|c| (c, colour)).collect())
+            .collect();
+        Ok(Self { cells: Grid::from_rows(rows)?, caption })
+    }
+}
+
+/// The ANSI escape codes that redraw the frame over the previous one
+pub fn to_ansi(frame: &Frame) -> String {
+    let mut ansi = String::from("\x1b[H");
+    for row in frame.cells.rows() {
+        let mut current: Option<Rgb> = None;
+        for &(c, colour) in row {
+            if current != Some(colour) {
+                let Rgb(r, g, b) = colour;
+                let _ = write!(ansi, "\x1b[38;2;{r};{g};{b}m");
+                current = Some(colour);
+            }
+            ansi.push(c);
+        }
+        ansi.push_str("\x1b[0m\x1b[K\n");
+    }
+    let _ = write!(ansi, "{}\x1b[K\n\x1b[J", frame.caption);
+    ansi
+}
+
+/// Runs `simulate` in the background and plays the frames it produces at `fps` frames per second.
+/// The simulation's error (if any) is only reported once every frame has been shown.
+pub fn run(simulate: impl FnOnce(&mut dyn FnMut(Frame)) -> Result<()> + Send + 'static, fps: f64) -> Result<()> {
+    // A small buffer keeps the simulation just ahead of the player, rather than finishing it up front
+    let (sender, frames) = mpsc::sync_channel(1);
+    let simulation = thread::spawn(move || {
+        simulate(&mut |frame| {
+            let _ = sender.send(frame);
+        })
+    });
+
+    let mut frames = frames.into_iter().peekable();
+    if frames.peek().is_some() && !play(frames, fps)? {
+        return Ok(());
+    }
+    simulation.join().map_err(|_| anyhow!("The simulation panicked"))?
+}
+
+/// Puts the terminal into a mode where keys are read as soon as they are pressed, until dropped
+struct Terminal {
+    saved_settings: Option<String>,
+}
+
+impl Terminal {
+    fn open() -> Result<Self> {
+        let mut saved_settings = None;
+        if io::stdin().is_terminal() {
+            let saved =
+                Command::new("stty").arg("-g").stdin(Stdio::inherit()).output().context("Could not run stty")?;
+            saved_settings = Some(String::from_utf8(saved.stdout)?.trim().to_string());
+            // Without isig, Ctrl-C arrives as a key so that the settings are still restored
+            Command::new("stty").args(["-icanon", "-echo", "-isig"]).stdin(Stdio::inherit()).status()?;
+        }
+        print!("\x1b[2J\x1b[?25l");
+        Ok(Self { saved_settings })
+    }
+
+    /// The keys pressed, or nothing if stdin is not a terminal
+    fn keys(&self) -> Option<Receiver<u8>> {
+        self.saved_settings.as_ref()?;
+        let (sender, keys) = mpsc::channel();
+        thread::spawn(move || {
+            for byte in io::stdin().lock().bytes() {
+                if byte.map(|b| sender.send(b)).is_err() {
+                    break;
+                }
+            }
+        });
+        Some(keys)
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        print!("\x1b[0m\x1b[?25h");
+        let _ = io::stdout().flush();
+        if let Some(settings) = &self.saved_settings {
+            let _ = Command::new("stty").arg(settings).stdin(Stdio::inherit()).status();
+        }
+    }
+}
+
+/// Draws each frame in turn, returning false if the viewer quit before the end.
+///
+/// Space pauses and resumes, n steps forward one frame while paused, + and - double and halve the speed, and q quits.
+fn play(frames: impl Iterator<Item = Frame>, fps: f64) -> Result<bool> {
+    let terminal = Terminal::open()?;
+    let keys = terminal.keys();
+    let mut delay = Duration::from_secs_f64(1.0 / fps.max(0.001));
+    let mut paused = false;
+    let mut stdout = io::stdout().lock();
+
+    for (n, frame) in frames.enumerate() {
+        let deadline = Instant::now() + delay;
+        loop {
+            let status = if paused { "paused" } else { "playing" };
+            let help = if keys.is_some() { ", [space] pause, [n] step, [+/-] speed, [q] quit" } else { "" };
+            let caption =
+                format!("{}\nframe {n}, {status} at {:.1} fps{help}", frame.caption, 1.0 / delay.as_secs_f64());
+            stdout.write_all(to_ansi(&Frame { caption, ..frame.clone() }).as_bytes())?;
+            stdout.flush()?;
+
+            let Some(keys) = &keys else {
+                thread::sleep(delay);
+                break;
+            };
+            let key = if paused {
+                keys.recv().map_err(|_| RecvTimeoutError::Disconnected)
+            } else {
+                keys.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            };
+            match key {
+                Ok(b' ') => paused = !paused,
+                Ok(b'n') if paused => break,
+                Ok(b'+' | b'=') => delay = (delay / 2).max(Duration::from_millis(1)),
+                Ok(b'-') => delay = (delay * 2).min(Duration::from_secs(10)),
+                Ok(b'q' | 3) => return Ok(false),
+                Ok(_) => {}
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return Ok(false),
+            }
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_ansi() {
+        let lines = ["ab".to_string(), "c".to_string()];
+        let mut frame = Frame::from_lines(&lines, Rgb::WHITE, "done".to_string()).unwrap();
+        frame.cells[(0, 1)].1 = Rgb::RED;
+        assert_eq!(
+            to_ansi(&frame),
+            "\x1b[H\x1b[38;2;255;255;255ma\x1b[38;2;220;40;40mb\x1b[0m\x1b[K\n\
+             \x1b[38;2;255;255;255mc \x1b[0m\x1b[K\n\
+             done\x1b[K\n\x1b[J"
+        );
+    }
+}
@@ -0,0 +1,30 @@
+//! Checks every solution against its puzzle input and the known answers in `answers.toml`, as `verify` does.
+
+use aoc_2023::{
+    answers::{Answers, Verdict},
+    parallel, solution,
+};
+
+#[test]
+fn test_known_answers() {
+    let answers = Answers::load("answers.toml").unwrap();
+    let registry = solution::registry();
+    let parts: Vec<_> = registry.iter().flat_map(|solution| [(solution, 1), (solution, 2)]).collect();
+    let jobs = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut failures = Vec::new();
+    parallel::map_in_order(
+        parts,
+        jobs,
+        |(solution, part)| {
+            let day = solution.day();
+            let input = std::fs::read_to_string(format!("inputs/day{day:02}.txt")).unwrap();
+            (day, part, Verdict::new(answers.get(day, part), solution::solve(solution.as_ref(), part, &input)))
+        },
+        |(day, part, verdict)| {
+            if verdict.is_failure() {
+                failures.push(format!("{day:02}-{part}: {verdict}"));
+            }
+        },
+    );
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
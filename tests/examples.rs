@@ -0,0 +1,12 @@
+//! Checks every example in `examples/` against the answers in its header, with one test per part. The tests are
+//! written by `build.rs`, which ignores the tests of parts that are listed as unsolved.
+
+use aoc_2023::examples::Example;
+
+fn check(path: &str, part: u8) {
+    if let Err(e) = Example::load(path).and_then(|example| example.check_part(part)) {
+        panic!("{path}:\n{e:#}");
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/examples.rs"));